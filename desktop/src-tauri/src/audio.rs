@@ -1,22 +1,29 @@
 #[cfg(windows)]
 mod windows_audio {
-    use crate::state::AudioDevice;
+    use super::Volume;
+    use crate::state::{AudioDevice, DeviceKind};
     use std::ffi::OsString;
     use std::os::windows::ffi::OsStringExt;
     use std::sync::mpsc;
     use windows::core::{implement, GUID, HRESULT, PCWSTR};
-    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Devices::FunctionDiscovery::{PKEY_Device_ContainerId, PKEY_Device_FriendlyName};
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
     use windows::Win32::Media::Audio::{
-        eConsole, eCommunications, eMultimedia, eRender, IMMDevice, IMMDeviceCollection,
-        IMMDeviceEnumerator, IMMNotificationClient, IMMNotificationClient_Impl,
-        MMDeviceEnumerator, DEVICE_STATE, DEVICE_STATE_ACTIVE, EDataFlow, ERole,
+        eConsole, eCommunications, eMultimedia, eRender, DigitalAudioDisplayDevice,
+        EndpointFormFactor, Handset, Headphones, Headset, IMMDevice, IMMDeviceCollection,
+        IMMDeviceEnumerator, IMMNotificationClient, IMMNotificationClient_Impl, LineLevel,
+        MMDeviceEnumerator, Speakers, DEVICE_STATE, DEVICE_STATE_ACTIVE, EDataFlow, ERole,
+        PKEY_AudioEndpoint_FormFactor,
+    };
+    use windows::Win32::System::Com::StructuredStorage::{
+        PropVariantToStringAlloc, PropVariantToUInt32,
     };
-    use windows::Win32::System::Com::StructuredStorage::PropVariantToStringAlloc;
     use windows::Win32::System::Com::{
         CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_APARTMENTTHREADED,
         COINIT_MULTITHREADED, STGM_READ,
     };
-    use windows::Win32::UI::Shell::PropertiesSystem::PROPERTYKEY;
+    use windows::Win32::UI::Shell::PropertiesSystem::{IPropertyStore, PROPERTYKEY};
 
     // IPolicyConfig COM interface GUIDs
     const CLSID_POLICY_CONFIG_CLIENT: GUID = GUID::from_u128(0x870af99c_171d_4f9e_af0d_e63df40c2bc9);
@@ -230,32 +237,125 @@ mod windows_audio {
 
             let id = pwstr_to_string(id_pwstr.0);
 
-            // Get friendly name from property store
-            let name = match device.OpenPropertyStore(STGM_READ) {
-                Ok(prop_store) => {
-                    match prop_store.GetValue(&PKEY_Device_FriendlyName) {
-                        Ok(name_prop) => {
-                            match PropVariantToStringAlloc(&name_prop) {
-                                Ok(name_pwstr) => pwstr_to_string(name_pwstr.0),
-                                Err(_) => format!("Device {}", i),
-                            }
-                        }
-                        Err(_) => format!("Device {}", i),
-                    }
-                }
-                Err(_) => format!("Device {}", i),
-            };
+            // Get friendly name and container from property store
+            let prop_store = device.OpenPropertyStore(STGM_READ).ok();
+            let name = prop_store
+                .as_ref()
+                .and_then(|store| read_string_property(store, &PKEY_Device_FriendlyName))
+                .unwrap_or_else(|| format!("Device {}", i));
+            let container_id = prop_store
+                .as_ref()
+                .and_then(|store| read_string_property(store, &PKEY_Device_ContainerId));
+            let kind = prop_store
+                .as_ref()
+                .and_then(|store| read_u32_property(store, &PKEY_AudioEndpoint_FormFactor))
+                .map_or(DeviceKind::Other, |value| device_kind(EndpointFormFactor(value as i32)));
 
             devices.push(AudioDevice {
                 id: id.clone(),
                 name,
                 is_default: id == default_id,
+                container_id,
+                alias: None,
+                kind,
             });
         }
 
         Ok(devices)
     }
 
+    unsafe fn read_string_property(store: &IPropertyStore, key: &PROPERTYKEY) -> Option<String> {
+        let value = store.GetValue(key).ok()?;
+        let pwstr = PropVariantToStringAlloc(&value).ok()?;
+        Some(pwstr_to_string(pwstr.0))
+    }
+
+    unsafe fn read_u32_property(store: &IPropertyStore, key: &PROPERTYKEY) -> Option<u32> {
+        let value = store.GetValue(key).ok()?;
+        PropVariantToUInt32(&value).ok()
+    }
+
+    fn device_kind(form_factor: EndpointFormFactor) -> DeviceKind {
+        match form_factor {
+            Speakers | LineLevel => DeviceKind::Speakers,
+            Headphones | Headset | Handset => DeviceKind::Headphones,
+            DigitalAudioDisplayDevice => DeviceKind::Display,
+            _ => DeviceKind::Other,
+        }
+    }
+
+    pub fn get_volume(device_id: &str) -> Result<Volume, String> {
+        unsafe {
+            // Initialize COM
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+            let result = endpoint_volume(device_id).and_then(|volume| {
+                Ok(Volume {
+                    level: volume
+                        .GetMasterVolumeLevelScalar()
+                        .map_err(|e| format!("Failed to read volume: {}", e))?,
+                    muted: volume
+                        .GetMute()
+                        .map_err(|e| format!("Failed to read mute state: {}", e))?
+                        .as_bool(),
+                })
+            });
+
+            CoUninitialize();
+
+            result
+        }
+    }
+
+    pub fn set_volume(device_id: &str, level: f32) -> Result<(), String> {
+        unsafe {
+            // Initialize COM
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+            let result = endpoint_volume(device_id).and_then(|volume| {
+                volume
+                    .SetMasterVolumeLevelScalar(level.clamp(0.0, 1.0), std::ptr::null())
+                    .map_err(|e| format!("Failed to set volume: {}", e))
+            });
+
+            CoUninitialize();
+
+            result
+        }
+    }
+
+    pub fn set_mute(device_id: &str, muted: bool) -> Result<(), String> {
+        unsafe {
+            // Initialize COM
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+            let result = endpoint_volume(device_id).and_then(|volume| {
+                volume
+                    .SetMute(BOOL::from(muted), std::ptr::null())
+                    .map_err(|e| format!("Failed to set mute state: {}", e))
+            });
+
+            CoUninitialize();
+
+            result
+        }
+    }
+
+    unsafe fn endpoint_volume(device_id: &str) -> Result<IAudioEndpointVolume, String> {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
+
+        let device_id_wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+        let device = enumerator
+            .GetDevice(PCWSTR(device_id_wide.as_ptr()))
+            .map_err(|e| format!("Device not found: {}", e))?;
+
+        device
+            .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to open device volume: {}", e))
+    }
+
     pub fn set_default_device(device_id: &str) -> Result<(), String> {
         unsafe {
             // Initialize COM
@@ -354,6 +454,14 @@ mod windows_audio {
 #[cfg(windows)]
 pub use windows_audio::*;
 
+// Master volume of an output device
+#[derive(Debug, Clone, Copy)]
+pub struct Volume {
+    // From 0.0 to 1.0
+    pub level: f32,
+    pub muted: bool,
+}
+
 #[cfg(not(windows))]
 pub fn get_audio_devices() -> Result<Vec<crate::state::AudioDevice>, String> {
     Err("Audio device enumeration is only supported on Windows".to_string())
@@ -364,6 +472,21 @@ pub fn set_default_device(_device_id: &str) -> Result<(), String> {
     Err("Setting default audio device is only supported on Windows".to_string())
 }
 
+#[cfg(not(windows))]
+pub fn get_volume(_device_id: &str) -> Result<Volume, String> {
+    Err("Reading device volume is only supported on Windows".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn set_volume(_device_id: &str, _level: f32) -> Result<(), String> {
+    Err("Setting device volume is only supported on Windows".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn set_mute(_device_id: &str, _muted: bool) -> Result<(), String> {
+    Err("Muting devices is only supported on Windows".to_string())
+}
+
 #[cfg(not(windows))]
 pub struct DeviceListenerHandle;
 
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(soundshift_lib::run_cli())
+}
@@ -0,0 +1,389 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::audio;
+use crate::identity;
+use crate::ipc;
+use crate::paths;
+use crate::state::{AppState, AudioDevice, DeviceGroup};
+use crate::switcher;
+
+const USAGE: &str = "Usage: soundshift-cli [--json] [--config <dir>] <command>
+
+Commands:
+  list                     List output devices
+  get-default              Show the default output device
+  set <device>             Make a device the default (ID, name or part of a name)
+  groups                   List device groups
+  cycle <group>            Switch a group to its next online device
+  select <group> <number>  Switch a group to its device at <number> (from 1)
+
+Groups can be given by name or ID.";
+
+enum Command {
+    List,
+    GetDefault,
+    Set(String),
+    Groups,
+    Cycle(String),
+    Select(String, usize),
+}
+
+#[derive(Serialize)]
+struct GroupSummary<'a> {
+    id: &'a str,
+    name: &'a str,
+    shortcut: Option<&'a str>,
+    devices: Vec<MemberSummary<'a>>,
+}
+
+#[derive(Serialize)]
+struct MemberSummary<'a> {
+    position: usize,
+    name: &'a str,
+    online: bool,
+    current: bool,
+}
+
+/// Runs the command-line interface and returns the process exit code.
+pub fn run(args: Vec<String>) -> i32 {
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    let json = args.iter().any(|a| a == "--json");
+
+    let command = match parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    match execute(command, json) {
+        Ok(()) => 0,
+        Err(e) => {
+            if json {
+                println!("{}", serde_json::json!({ "error": e }));
+            } else {
+                eprintln!("Error: {}", e);
+            }
+            1
+        }
+    }
+}
+
+fn parse(args: &[String]) -> Result<Command, String> {
+    // Global flags are handled elsewhere (`--config` by paths), so skip them here
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" | "--portable" => {}
+            "--config" => {
+                iter.next();
+            }
+            a if a.starts_with("--config=") => {}
+            a if a.starts_with("--") => return Err(format!("Unknown option {}", a)),
+            a => positional.push(a),
+        }
+    }
+
+    match positional.as_slice() {
+        ["list"] => Ok(Command::List),
+        ["get-default"] => Ok(Command::GetDefault),
+        ["set", device] => Ok(Command::Set(device.to_string())),
+        ["groups"] => Ok(Command::Groups),
+        ["cycle", group] => Ok(Command::Cycle(group.to_string())),
+        ["select", group, number] => number
+            .parse()
+            .map_err(|_| format!("Invalid device number {}", number))
+            .and_then(switcher::position_index)
+            .map(|index| Command::Select(group.to_string(), index)),
+        [] => Err("No command given".to_string()),
+        [command, ..] => Err(format!("Unknown command or wrong arguments: {}", command)),
+    }
+}
+
+fn execute(command: Command, json: bool) -> Result<(), String> {
+    let mut backend = Backend::open()?;
+
+    match command {
+        Command::List => {
+            let devices = backend.devices()?;
+            if json {
+                print_json(&devices);
+            } else {
+                for device in &devices {
+                    let marker = if device.is_default { "*" } else { " " };
+                    println!("{} {}  ({})", marker, device.display_name(), device.id);
+                }
+            }
+        }
+        Command::GetDefault => {
+            let devices = backend.devices()?;
+            let device = devices
+                .iter()
+                .find(|d| d.is_default)
+                .ok_or("No default output device")?;
+            print_device(device, json);
+        }
+        Command::Set(query) => {
+            let devices = backend.devices()?;
+            let mut device = switcher::lookup_device(&devices, &query)?.clone();
+            backend.set_default(&device.id)?;
+            device.is_default = true;
+            print_device(&device, json);
+        }
+        Command::Groups => {
+            let devices = backend.devices()?;
+            let groups = backend.groups()?;
+            let aliases = backend.aliases()?;
+            let summaries: Vec<GroupSummary> = groups
+                .iter()
+                .map(|group| summarize(group, &devices, &aliases))
+                .collect();
+
+            if json {
+                print_json(&summaries);
+            } else {
+                for group in &summaries {
+                    match group.shortcut {
+                        Some(shortcut) => println!("{}  [{}]", group.name, shortcut),
+                        None => println!("{}", group.name),
+                    }
+                    for member in &group.devices {
+                        let marker = if member.current { "*" } else { " " };
+                        let status = if member.online { "" } else { "  (offline)" };
+                        println!(
+                            "  {} {}. {}{}",
+                            marker, member.position, member.name, status
+                        );
+                    }
+                }
+            }
+        }
+        Command::Cycle(query) => {
+            let group_id = find_group(&backend.groups()?, &query)?;
+            let device = backend.cycle(&group_id)?;
+            print_device(&device, json);
+        }
+        Command::Select(query, index) => {
+            let group_id = find_group(&backend.groups()?, &query)?;
+            let device = backend.select(&group_id, index)?;
+            print_device(&device, json);
+        }
+    }
+
+    Ok(())
+}
+
+// Commands go through the running app when there is one, so its state and
+// notifications stay in step; otherwise they work on the store file directly
+enum Backend {
+    Remote(ipc::Client),
+    Local { store: StoreFile, state: AppState },
+}
+
+impl Backend {
+    fn open() -> Result<Self, String> {
+        if let Some(client) = ipc::Client::connect() {
+            return Ok(Backend::Remote(client));
+        }
+
+        let store = StoreFile::load()?;
+        let state = AppState {
+            groups: store.get("groups").unwrap_or_default(),
+            aliases: store.get("aliases").unwrap_or_default(),
+            ..Default::default()
+        };
+        Ok(Backend::Local { store, state })
+    }
+
+    fn devices(&mut self) -> Result<Vec<AudioDevice>, String> {
+        match self {
+            Backend::Remote(client) => client.call("get_audio_devices", Value::Null),
+            Backend::Local { state, .. } => {
+                let mut devices = audio::get_audio_devices()?;
+                state.apply_aliases(&mut devices);
+                Ok(devices)
+            }
+        }
+    }
+
+    fn groups(&mut self) -> Result<Vec<DeviceGroup>, String> {
+        match self {
+            Backend::Remote(client) => client.call("get_groups", Value::Null),
+            Backend::Local { state, .. } => Ok(state.groups.clone()),
+        }
+    }
+
+    fn aliases(&mut self) -> Result<HashMap<String, String>, String> {
+        match self {
+            Backend::Remote(client) => client.call("get_device_aliases", Value::Null),
+            Backend::Local { state, .. } => Ok(state.aliases.clone()),
+        }
+    }
+
+    fn set_default(&mut self, device_id: &str) -> Result<(), String> {
+        match self {
+            Backend::Remote(client) => {
+                client.call("set_default_device", json!({ "device_id": device_id }))
+            }
+            Backend::Local { .. } => audio::set_default_device(device_id),
+        }
+    }
+
+    fn cycle(&mut self, group_id: &str) -> Result<AudioDevice, String> {
+        match self {
+            Backend::Remote(client) => client.call("cycle_group", json!({ "group_id": group_id })),
+            Backend::Local { store, state } => {
+                switch_local(store, state, group_id, switcher::next_member)
+            }
+        }
+    }
+
+    fn select(&mut self, group_id: &str, index: usize) -> Result<AudioDevice, String> {
+        match self {
+            Backend::Remote(client) => client.call(
+                "select_group_device",
+                json!({ "group_id": group_id, "device_index": index }),
+            ),
+            Backend::Local { store, state } => {
+                switch_local(store, state, group_id, |group, online| {
+                    switcher::member_at(group, online, index)
+                })
+            }
+        }
+    }
+}
+
+// Mirrors the app's switcher, but saves straight to the store file
+fn switch_local<F>(
+    store: &mut StoreFile,
+    state: &mut AppState,
+    group_id: &str,
+    pick: F,
+) -> Result<AudioDevice, String>
+where
+    F: FnOnce(&DeviceGroup, &[AudioDevice]) -> Result<(usize, AudioDevice), String>,
+{
+    let mut online = audio::get_audio_devices()?;
+    let group_index = state
+        .groups
+        .iter()
+        .position(|g| g.id == group_id)
+        .ok_or("Group not found")?;
+
+    // Devices that came back under a new ID count as online again
+    let relinks = identity::relink_group(&mut state.groups[group_index], &online);
+    state.follow_relinks(&relinks);
+    state.apply_aliases(&mut online);
+
+    let group = &mut state.groups[group_index];
+    let (index, mut device) = pick(group, &online)?;
+    group.current_index = index;
+    group.current_device_id = Some(device.id.clone());
+
+    audio::set_default_device(&device.id)?;
+    device.is_default = true;
+
+    store.set("groups", &state.groups);
+    store.set("aliases", &state.aliases);
+    store.save()?;
+
+    Ok(device)
+}
+
+fn find_group(groups: &[DeviceGroup], query: &str) -> Result<String, String> {
+    switcher::find_group(groups, query)
+        .map(|g| g.id.clone())
+        .ok_or_else(|| format!("No group named {}", query))
+}
+
+fn summarize<'a>(
+    group: &'a DeviceGroup,
+    online: &[AudioDevice],
+    aliases: &'a HashMap<String, String>,
+) -> GroupSummary<'a> {
+    let members = identity::resolve_members(group, online);
+
+    GroupSummary {
+        id: &group.id,
+        name: &group.name,
+        shortcut: group.shortcut.as_deref(),
+        devices: group
+            .devices
+            .iter()
+            .enumerate()
+            .map(|(i, device)| MemberSummary {
+                position: i + 1,
+                name: aliases.get(&device.id).unwrap_or(&device.name),
+                online: members.iter().any(|(index, _)| *index == i),
+                current: i == group.current_index,
+            })
+            .collect(),
+    }
+}
+
+fn print_device(device: &AudioDevice, json: bool) {
+    if json {
+        print_json(device);
+    } else {
+        println!("{}", device.display_name());
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(text) => println!("{}", text),
+        Err(e) => eprintln!("Failed to serialize output: {}", e),
+    }
+}
+
+// The app's store file, read and written without a running Tauri app
+struct StoreFile {
+    path: PathBuf,
+    values: Map<String, Value>,
+}
+
+impl StoreFile {
+    fn load() -> Result<Self, String> {
+        let path = paths::store_path();
+        let values = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+            // No store yet just means nothing has been configured
+            Err(_) => Map::new(),
+        };
+
+        Ok(Self { path, values })
+    }
+
+    fn get<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.values
+            .get(key)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    fn set<T: Serialize>(&mut self, key: &str, value: &T) {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.values.insert(key.to_string(), value);
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+
+        let contents = serde_json::to_string_pretty(&self.values).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+}
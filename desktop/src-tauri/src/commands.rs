@@ -1,22 +1,45 @@
+use std::collections::HashMap;
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{Manager, State};
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 use tauri_plugin_store::StoreExt;
 use uuid::Uuid;
 
-use tauri_plugin_notification::NotificationExt;
-
 use crate::audio;
-use crate::state::{AppSettings, AppState, AudioDevice, DeviceGroup, GroupDevice};
+use crate::config_file;
+use crate::hooks;
+use crate::http_api;
+use crate::identity;
+use crate::inventory;
+use crate::mqtt;
+use crate::osc;
+use crate::osd;
+use crate::paths;
+use crate::sound;
+use crate::speech;
+use crate::state::{
+    AppSettings, AppState, AudioDevice, DeviceGroup, GroupDevice, GroupHooks, InventoryEntry,
+    SwitchSource,
+};
+use crate::switcher;
+use crate::tray;
+use crate::webhooks;
 
 #[tauri::command]
-pub fn get_audio_devices() -> Result<Vec<AudioDevice>, String> {
-    audio::get_audio_devices()
+pub fn get_audio_devices(state: State<'_, Mutex<AppState>>) -> Result<Vec<AudioDevice>, String> {
+    let mut devices = audio::get_audio_devices()?;
+    state.lock().unwrap().apply_aliases(&mut devices);
+    Ok(devices)
 }
 
+// Switches run hooks that can take a while, so they stay off the main thread
 #[tauri::command]
-pub fn set_default_device(device_id: String) -> Result<(), String> {
-    audio::set_default_device(&device_id)
+pub async fn set_default_device(app: tauri::AppHandle, device_id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        switcher::set_default_device(&app, &device_id, SwitchSource::App).map(|_| ())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
@@ -32,6 +55,8 @@ pub fn create_group(
     name: String,
     devices: Vec<GroupDevice>,
     shortcut: Option<String>,
+    hooks: Option<GroupHooks>,
+    hide_notifications: Option<bool>,
 ) -> Result<DeviceGroup, String> {
     let group = DeviceGroup {
         id: Uuid::new_v4().to_string(),
@@ -39,8 +64,14 @@ pub fn create_group(
         devices,
         shortcut: shortcut.clone(),
         current_index: 0,
+        current_device_id: None,
+        relinks: Vec::new(),
+        hooks: hooks.unwrap_or_default(),
+        hide_notifications: hide_notifications.unwrap_or(false),
     };
 
+    identity::validate_group(&group)?;
+
     // Register shortcut if provided
     if let Some(ref sc) = shortcut {
         app.global_shortcut()
@@ -66,6 +97,8 @@ pub fn update_group(
     state: State<'_, Mutex<AppState>>,
     group: DeviceGroup,
 ) -> Result<(), String> {
+    identity::validate_group(&group)?;
+
     let mut state_guard = state.lock().unwrap();
 
     // Find the existing group
@@ -90,9 +123,10 @@ pub fn update_group(
             }
         }
 
-        // Update the group
+        // Update the group, keeping history the frontend doesn't edit
         if let Some(g) = state_guard.groups.iter_mut().find(|g| g.id == group.id) {
-            *g = group;
+            let relinks = std::mem::take(&mut g.relinks);
+            *g = DeviceGroup { relinks, ..group };
         }
     } else {
         return Err("Group not found".to_string());
@@ -135,116 +169,70 @@ pub fn delete_group(
 }
 
 #[tauri::command]
-pub fn cycle_group(
+pub async fn cycle_group(app: tauri::AppHandle, group_id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        switcher::cycle_group(&app, &group_id, SwitchSource::App).map(|_| ())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn select_group_device(
     app: tauri::AppHandle,
-    state: State<'_, Mutex<AppState>>,
     group_id: String,
+    device_index: usize,
 ) -> Result<(), String> {
-    // Get online device IDs first
-    let online_devices = audio::get_audio_devices().unwrap_or_default();
-    let online_ids: Vec<&str> = online_devices.iter().map(|d| d.id.as_str()).collect();
-
-    let mut state_guard = state.lock().unwrap();
-
-    let group = state_guard
-        .groups
-        .iter_mut()
-        .find(|g| g.id == group_id)
-        .ok_or("Group not found")?;
-
-    if group.devices.is_empty() {
-        return Err("Group has no devices".to_string());
-    }
-
-    // Find online devices in this group
-    let online_indices: Vec<usize> = group
-        .devices
-        .iter()
-        .enumerate()
-        .filter(|(_, d)| online_ids.contains(&d.id.as_str()))
-        .map(|(i, _)| i)
-        .collect();
-
-    if online_indices.is_empty() {
-        return Err("No online devices in group".to_string());
-    }
-
-    // Find current position in online devices and cycle to next
-    let current_online_pos = online_indices
-        .iter()
-        .position(|&i| i == group.current_index)
-        .unwrap_or(0);
-    let next_online_pos = (current_online_pos + 1) % online_indices.len();
-    group.current_index = online_indices[next_online_pos];
-
-    let device = group.devices[group.current_index].clone();
-
-    drop(state_guard);
+    tauri::async_runtime::spawn_blocking(move || {
+        switcher::select_group_device(&app, &group_id, device_index, SwitchSource::App).map(|_| ())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    // Set as default device
-    audio::set_default_device(&device.id)?;
+#[tauri::command]
+pub fn get_device_inventory(state: State<'_, Mutex<AppState>>) -> Vec<InventoryEntry> {
+    let state = state.lock().unwrap();
+    let mut entries: Vec<InventoryEntry> = state.inventory.values().cloned().collect();
+    entries.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    entries
+}
 
-    // Show notification
-    let _ = app.notification()
-        .builder()
-        .title("SoundShift")
-        .body(format!("Switched to {}", &device.name))
-        .show();
+#[tauri::command]
+pub fn prune_device_inventory(app: tauri::AppHandle, older_than_days: u64) -> usize {
+    inventory::prune(&app, older_than_days)
+}
 
-    // Save to store
-    save_groups(&app, &state)?;
+#[tauri::command]
+pub fn forget_device(app: tauri::AppHandle, device_id: String) -> Result<(), String> {
+    inventory::forget(&app, &device_id)
+}
 
-    Ok(())
+#[tauri::command]
+pub fn get_device_aliases(state: State<'_, Mutex<AppState>>) -> HashMap<String, String> {
+    let state = state.lock().unwrap();
+    state.aliases.clone()
 }
 
 #[tauri::command]
-pub fn select_group_device(
+pub fn set_device_alias(
     app: tauri::AppHandle,
     state: State<'_, Mutex<AppState>>,
-    group_id: String,
-    device_index: usize,
+    device_id: String,
+    alias: Option<String>,
 ) -> Result<(), String> {
-    // Check if device is online
-    let online_devices = audio::get_audio_devices().unwrap_or_default();
-    let online_ids: Vec<&str> = online_devices.iter().map(|d| d.id.as_str()).collect();
-
-    let mut state_guard = state.lock().unwrap();
-
-    let group = state_guard
-        .groups
-        .iter_mut()
-        .find(|g| g.id == group_id)
-        .ok_or("Group not found")?;
-
-    if device_index >= group.devices.len() {
-        return Err("Invalid device index".to_string());
-    }
-
-    let device = &group.devices[device_index];
+    {
+        let mut state = state.lock().unwrap();
 
-    // Check if device is online
-    if !online_ids.contains(&device.id.as_str()) {
-        return Err("Device is offline".to_string());
+        // An empty alias clears it, falling back to the OS name
+        match alias.map(|a| a.trim().to_string()).filter(|a| !a.is_empty()) {
+            Some(alias) => state.aliases.insert(device_id, alias),
+            None => state.aliases.remove(&device_id),
+        };
     }
 
-    // Set the selected device as current
-    group.current_index = device_index;
-    let device = group.devices[device_index].clone();
-
-    drop(state_guard);
-
-    // Set as default device
-    audio::set_default_device(&device.id)?;
-
-    // Show notification
-    let _ = app.notification()
-        .builder()
-        .title("SoundShift")
-        .body(format!("Switched to {}", &device.name))
-        .show();
-
     // Save to store
-    save_groups(&app, &state)?;
+    save_aliases(&app, &state);
 
     Ok(())
 }
@@ -263,20 +251,119 @@ pub fn update_settings(
 ) -> Result<(), String> {
     {
         let mut state = state.lock().unwrap();
-        state.settings = settings.clone();
+        state.settings = settings;
     }
 
     // Save to store
-    if let Ok(store) = app.store("config.json") {
-        store
-            .set("settings", serde_json::to_value(&settings).unwrap());
+    save_settings(&app, &state);
+    settings_changed(&app);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_config_file_path() -> Option<String> {
+    let path = config_file::config_file_path();
+    path.exists().then(|| path.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+pub fn get_config_dir() -> paths::ConfigDir {
+    paths::config_dir().clone()
+}
+
+#[tauri::command]
+pub fn export_config_file(app: tauri::AppHandle) -> Result<String, String> {
+    let path = config_file::export(&app)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+pub fn get_webhook_deliveries() -> Vec<webhooks::Delivery> {
+    webhooks::deliveries()
+}
+
+#[tauri::command]
+pub fn send_test_webhook(app: tauri::AppHandle) -> Result<(), String> {
+    webhooks::send_test(&app)
+}
+
+#[tauri::command]
+pub fn get_hook_runs() -> Vec<hooks::HookRun> {
+    hooks::runs()
+}
+
+#[tauri::command]
+pub fn get_osd() -> Option<osd::Osd> {
+    osd::current()
+}
+
+#[tauri::command]
+pub fn preview_osd(app: tauri::AppHandle) -> Result<(), String> {
+    osd::preview(&app)
+}
+
+// Waits for the chime to finish, off the main thread, so errors reach the frontend
+#[tauri::command]
+pub async fn test_chime(app: tauri::AppHandle, device_id: String) -> Result<(), String> {
+    let device = online_device(&device_id)?;
+    tauri::async_runtime::spawn_blocking(move || sound::test_chime(&app, &device))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn get_output_channels(device_id: String) -> Result<Vec<String>, String> {
+    sound::output_channels(&online_device(&device_id)?)
+}
+
+// Waits for the tone to finish, off the main thread, so errors reach the frontend
+#[tauri::command]
+pub async fn play_test_tone(device_id: String, channel: Option<u16>) -> Result<(), String> {
+    let device = online_device(&device_id)?;
+    tauri::async_runtime::spawn_blocking(move || sound::play_test_tone(&device, channel))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Waits for the speech engine to start, off the main thread
+#[tauri::command]
+pub async fn test_announcement(app: tauri::AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let devices = get_audio_devices(app.state())?;
+        let device = devices
+            .iter()
+            .find(|d| d.is_default)
+            .ok_or("No default output device")?;
+        speech::say(device.display_name())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn online_device(device_id: &str) -> Result<AudioDevice, String> {
+    audio::get_audio_devices()?
+        .into_iter()
+        .find(|d| d.id == device_id)
+        .ok_or_else(|| "Device is offline".to_string())
+}
+
+pub(crate) fn save_aliases(app: &tauri::AppHandle, state: &State<'_, Mutex<AppState>>) {
+    let aliases = {
+        let state = state.lock().unwrap();
+        state.aliases.clone()
+    };
+
+    if let Ok(store) = app.store(paths::store_path()) {
+        store.set("aliases", serde_json::to_value(&aliases).unwrap());
         let _ = store.save();
     }
 
-    Ok(())
+    config_file::sync(app);
+    tray::refresh(app);
 }
 
-fn save_groups(
+pub(crate) fn save_groups(
     app: &tauri::AppHandle,
     state: &State<'_, Mutex<AppState>>,
 ) -> Result<(), String> {
@@ -285,10 +372,35 @@ fn save_groups(
         state.groups.clone()
     };
 
-    if let Ok(store) = app.store("config.json") {
+    if let Ok(store) = app.store(paths::store_path()) {
         store.set("groups", serde_json::to_value(&groups).unwrap());
         let _ = store.save();
     }
 
+    config_file::sync(app);
+    tray::refresh(app);
+
     Ok(())
 }
+
+pub(crate) fn save_settings(app: &tauri::AppHandle, state: &State<'_, Mutex<AppState>>) {
+    let settings = {
+        let state = state.lock().unwrap();
+        state.settings.clone()
+    };
+
+    if let Ok(store) = app.store(paths::store_path()) {
+        store.set("settings", serde_json::to_value(&settings).unwrap());
+        let _ = store.save();
+    }
+
+    config_file::sync(app);
+}
+
+/// Starts, stops or reconfigures the integrations after settings change.
+pub(crate) fn settings_changed(app: &tauri::AppHandle) {
+    http_api::sync(app);
+    mqtt::sync(app);
+    osc::sync(app);
+    osd::sync(app);
+}
@@ -0,0 +1,388 @@
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tauri_plugin_store::StoreExt;
+use uuid::Uuid;
+
+use crate::commands;
+use crate::paths;
+use crate::state::{AppSettings, AppState, DeviceGroup};
+
+pub const CONFIG_FILE_NAME: &str = "soundshift.toml";
+
+// Group fields that change at runtime and would churn the file on every switch
+const RUNTIME_GROUP_KEYS: &[&str] = &["current_index", "current_device_id", "relinks"];
+
+// Settings left out of the file so it can be shared or kept in a dotfiles
+// repository, by table under [settings] and key. They stay in the app's own
+// store, and a file that sets one by hand still overrides it.
+const SECRET_SETTINGS: &[Secret] = &[
+    ("http_api", "token", |settings| &mut settings.http_api.token),
+    ("mqtt", "password", |settings| &mut settings.mqtt.password),
+];
+
+type Secret = (
+    &'static str,
+    &'static str,
+    fn(&mut AppSettings) -> &mut String,
+);
+
+// Contents last written or applied, so our own writes don't trigger a reload
+static LAST_SYNCED: Mutex<Option<String>> = Mutex::new(None);
+
+// Layout of the human-editable config file
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    settings: AppSettings,
+    // Left alone when the section is missing, e.g. in files written before aliases
+    #[serde(default)]
+    aliases: Option<HashMap<String, String>>,
+    #[serde(default, rename = "group")]
+    groups: Vec<DeviceGroup>,
+    // Secrets the file doesn't set, which keep their stored values
+    #[serde(skip)]
+    omitted_secrets: Vec<Secret>,
+}
+
+#[derive(Serialize)]
+struct RenderedConfig<'a> {
+    settings: toml::Value,
+    // Sorted so rewriting unchanged state produces an identical file
+    aliases: BTreeMap<&'a str, &'a str>,
+    group: Vec<toml::Value>,
+}
+
+pub fn config_file_path() -> PathBuf {
+    paths::config_dir().path.join(CONFIG_FILE_NAME)
+}
+
+/// Loads the config file if it exists and applies it to the app state.
+/// Validation errors are reported to the frontend and leave the state untouched.
+pub fn reload(app: &AppHandle) {
+    let path = config_file_path();
+
+    // A missing file just means the feature isn't in use
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => return,
+    };
+
+    if LAST_SYNCED.lock().unwrap().as_deref() == Some(contents.as_str()) {
+        return;
+    }
+
+    match parse(&contents) {
+        Ok(config) => {
+            *LAST_SYNCED.lock().unwrap() = Some(contents);
+            apply(app, config);
+        }
+        Err(e) => {
+            let message = format!("{}: {}", CONFIG_FILE_NAME, e);
+            eprintln!("{}", message);
+            let _ = app.emit("config-error", message);
+        }
+    }
+}
+
+/// Writes the current state to the config file, but only if the user has opted in
+/// by creating it. Keeps GUI changes from being reverted by the next external edit.
+pub fn sync(app: &AppHandle) {
+    let path = config_file_path();
+    if path.exists() {
+        if let Err(e) = write(app, &path) {
+            eprintln!("Failed to write {}: {}", CONFIG_FILE_NAME, e);
+        }
+    }
+}
+
+/// Creates (or overwrites) the config file from the current state.
+pub fn export(app: &AppHandle) -> Result<PathBuf, String> {
+    let path = config_file_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    write(app, &path)?;
+    Ok(path)
+}
+
+/// Watches the config directory and reloads the file whenever it changes on disk.
+pub fn start_watcher(app: AppHandle) -> Result<(), String> {
+    let path = config_file_path();
+    let dir = path
+        .parent()
+        .ok_or("Config file has no parent directory")?
+        .to_path_buf();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("Failed to create config watcher: {}", e))?;
+
+    // Watch the directory rather than the file so editors that save by
+    // replacing the file don't break the watch
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch config directory: {}", e))?;
+
+    std::thread::spawn(move || {
+        // The watcher stops when dropped, so keep it alive on this thread
+        let _watcher = watcher;
+
+        while let Ok(event) = rx.recv() {
+            let touches_config = match event {
+                Ok(event) => event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == path.file_name()),
+                Err(_) => false,
+            };
+
+            if !touches_config {
+                continue;
+            }
+
+            // Debounce: editors often write in several steps
+            std::thread::sleep(Duration::from_millis(200));
+            while rx.try_recv().is_ok() {}
+
+            reload(&app);
+        }
+    });
+
+    Ok(())
+}
+
+fn parse(contents: &str) -> Result<ConfigFile, String> {
+    let mut config: ConfigFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+    validate(&config.groups)?;
+
+    let raw: toml::Table = contents
+        .parse()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+    config.omitted_secrets = SECRET_SETTINGS
+        .iter()
+        .filter(|(table, key, _)| {
+            raw.get("settings")
+                .and_then(|settings| settings.get(table))
+                .and_then(|table| table.get(key))
+                .is_none()
+        })
+        .copied()
+        .collect();
+
+    Ok(config)
+}
+
+fn validate(groups: &[DeviceGroup]) -> Result<(), String> {
+    let mut errors = Vec::new();
+    let mut names = HashSet::new();
+    let mut ids = HashSet::new();
+    let mut shortcuts = HashSet::new();
+
+    for (i, group) in groups.iter().enumerate() {
+        let label = if group.name.trim().is_empty() {
+            format!("group {}", i + 1)
+        } else {
+            format!("group \"{}\"", group.name)
+        };
+
+        if group.name.trim().is_empty() {
+            errors.push(format!("{} has an empty name", label));
+        } else if !names.insert(group.name.to_lowercase()) {
+            errors.push(format!("{} is defined more than once", label));
+        }
+
+        if !group.id.is_empty() && !ids.insert(group.id.as_str()) {
+            errors.push(format!("{} reuses id {}", label, group.id));
+        }
+
+        for (j, device) in group.devices.iter().enumerate() {
+            match device.matcher {
+                Some(ref matcher) => {
+                    if let Err(e) = matcher.validate() {
+                        errors.push(format!("{} device {}: {}", label, j + 1, e));
+                    }
+                }
+                None if device.id.trim().is_empty() => {
+                    errors.push(format!("{} device {} has an empty id", label, j + 1));
+                }
+                None => {}
+            }
+            if let Some(ref pattern) = device.match_pattern {
+                if let Err(e) = pattern.validate() {
+                    errors.push(format!("{} device {}: {}", label, j + 1, e));
+                }
+            }
+        }
+
+        if let Some(ref shortcut) = group.shortcut {
+            match shortcut.parse::<Shortcut>() {
+                Ok(parsed) => {
+                    if !shortcuts.insert(parsed) {
+                        errors.push(format!("{} reuses shortcut {}", label, shortcut));
+                    }
+                }
+                Err(e) => errors.push(format!(
+                    "{} has invalid shortcut {}: {}",
+                    label, shortcut, e
+                )),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn apply(app: &AppHandle, mut config: ConfigFile) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut state_guard = state.lock().unwrap();
+
+    let mut groups = config.groups;
+    for group in &mut groups {
+        // Groups written by hand may omit the id, so match them up by name
+        let existing = if group.id.is_empty() {
+            state_guard.groups.iter().find(|g| g.name == group.name)
+        } else {
+            state_guard.groups.iter().find(|g| g.id == group.id)
+        };
+
+        match existing {
+            Some(existing) => {
+                group.id = existing.id.clone();
+                group.current_index = existing.current_index;
+                group.current_device_id = existing.current_device_id.clone();
+                group.relinks = existing.relinks.clone();
+            }
+            None if group.id.is_empty() => group.id = Uuid::new_v4().to_string(),
+            None => {}
+        }
+
+        // Pattern entries only need the matcher, so give them a stable label
+        for device in &mut group.devices {
+            if let Some(ref matcher) = device.matcher {
+                if device.id.is_empty() {
+                    device.id = format!("pattern:{}", matcher.pattern());
+                }
+                if device.name.is_empty() {
+                    device.name = matcher.pattern().to_string();
+                }
+            }
+        }
+
+        if group.current_index >= group.devices.len() {
+            group.current_index = 0;
+        }
+    }
+
+    // Re-register shortcuts for the new set of groups
+    let _ = app.global_shortcut().unregister_all();
+    for group in &groups {
+        if let Some(ref shortcut) = group.shortcut {
+            if let Err(e) = app.global_shortcut().register(shortcut.as_str()) {
+                eprintln!("Failed to register shortcut {}: {}", shortcut, e);
+            }
+        }
+    }
+
+    if config.settings.run_on_startup != state_guard.settings.run_on_startup {
+        let result = if config.settings.run_on_startup {
+            app.autolaunch().enable()
+        } else {
+            app.autolaunch().disable()
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to update autostart: {}", e);
+        }
+    }
+
+    for (_, _, field) in &config.omitted_secrets {
+        *field(&mut config.settings) = std::mem::take(field(&mut state_guard.settings));
+    }
+
+    state_guard.groups = groups;
+    state_guard.settings = config.settings;
+    if let Some(aliases) = config.aliases {
+        state_guard.aliases = aliases;
+    }
+
+    // Save to store directly, so the user's file isn't rewritten underneath them
+    if let Ok(store) = app.store(paths::store_path()) {
+        store.set("groups", serde_json::to_value(&state_guard.groups).unwrap());
+        store.set(
+            "settings",
+            serde_json::to_value(&state_guard.settings).unwrap(),
+        );
+        store.set(
+            "aliases",
+            serde_json::to_value(&state_guard.aliases).unwrap(),
+        );
+        let _ = store.save();
+    }
+
+    drop(state_guard);
+
+    commands::settings_changed(app);
+    let _ = app.emit("config-reloaded", ());
+}
+
+fn write(app: &AppHandle, path: &PathBuf) -> Result<(), String> {
+    let contents = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        render(&state)?
+    };
+
+    let mut last_synced = LAST_SYNCED.lock().unwrap();
+    if last_synced.as_deref() == Some(contents.as_str()) && path.exists() {
+        return Ok(());
+    }
+
+    std::fs::write(path, &contents).map_err(|e| e.to_string())?;
+    *last_synced = Some(contents);
+
+    Ok(())
+}
+
+fn render(state: &AppState) -> Result<String, String> {
+    let mut groups = Vec::new();
+    for group in &state.groups {
+        let mut value = toml::Value::try_from(group).map_err(|e| e.to_string())?;
+        if let Some(table) = value.as_table_mut() {
+            for key in RUNTIME_GROUP_KEYS {
+                table.remove(*key);
+            }
+        }
+        groups.push(value);
+    }
+
+    let mut settings = toml::Value::try_from(&state.settings).map_err(|e| e.to_string())?;
+    for (table, key, _) in SECRET_SETTINGS {
+        if let Some(table) = settings.get_mut(table).and_then(|t| t.as_table_mut()) {
+            table.remove(*key);
+        }
+    }
+
+    toml::to_string_pretty(&RenderedConfig {
+        settings,
+        aliases: state
+            .aliases
+            .iter()
+            .map(|(id, alias)| (id.as_str(), alias.as_str()))
+            .collect(),
+        group: groups,
+    })
+    .map_err(|e| e.to_string())
+}
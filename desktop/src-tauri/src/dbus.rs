@@ -0,0 +1,150 @@
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use tauri::{AppHandle, Listener, Manager};
+use zbus::blocking::connection::Builder;
+use zbus::fdo;
+use zbus::interface;
+use zbus::object_server::SignalEmitter;
+
+use crate::commands;
+use crate::state::{AppState, AudioDevice, SwitchSource};
+use crate::switcher;
+
+const BUS_NAME: &str = "com.soundshift.App";
+const OBJECT_PATH: &str = "/com/soundshift/App";
+
+// Signals waiting to go out, sent from their own thread so a switch made over
+// D-Bus never waits on the bus it was called from
+static SIGNALS: Mutex<Option<Sender<Signal>>> = Mutex::new(None);
+
+enum Signal {
+    DeviceSwitched {
+        id: String,
+        name: String,
+        group: String,
+    },
+    DevicesChanged,
+}
+
+struct Service {
+    app: AppHandle,
+}
+
+#[interface(name = "com.soundshift.App1")]
+impl Service {
+    /// Output devices as (id, name, is_default), using aliases for names.
+    #[zbus(out_args("devices"))]
+    fn list_devices(&self) -> fdo::Result<Vec<(String, String, bool)>> {
+        let devices = commands::get_audio_devices(self.app.state()).map_err(fdo::Error::Failed)?;
+        Ok(devices
+            .iter()
+            .map(|d| (d.id.clone(), d.display_name().to_string(), d.is_default))
+            .collect())
+    }
+
+    /// Makes a device the default, given its ID, name or alias. Returns (id, name).
+    #[zbus(out_args("id", "name"))]
+    fn set_default(&self, device: &str) -> fdo::Result<(String, String)> {
+        commands::get_audio_devices(self.app.state())
+            .and_then(|devices| {
+                switcher::lookup_device(&devices, device).map(|device| device.id.clone())
+            })
+            .and_then(|id| switcher::set_default_device(&self.app, &id, SwitchSource::Dbus))
+            .map(summary)
+            .map_err(fdo::Error::Failed)
+    }
+
+    /// Switches a group, given its name or ID, to its next online device. Returns (id, name).
+    #[zbus(out_args("id", "name"))]
+    fn cycle_group(&self, group: &str) -> fdo::Result<(String, String)> {
+        let group_id = {
+            let state = self.app.state::<Mutex<AppState>>();
+            let state = state.lock().unwrap();
+            switcher::find_group(&state.groups, group)
+                .map(|g| g.id.clone())
+                .ok_or_else(|| fdo::Error::Failed(format!("No group named {}", group)))?
+        };
+
+        switcher::cycle_group(&self.app, &group_id, SwitchSource::Dbus)
+            .map(summary)
+            .map_err(fdo::Error::Failed)
+    }
+
+    /// Group is empty when the device was picked directly rather than through a group.
+    #[zbus(signal)]
+    async fn device_switched(
+        emitter: &SignalEmitter<'_>,
+        id: &str,
+        name: &str,
+        group: &str,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn devices_changed(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+/// Claims the bus name, serves the interface and relays switches as signals.
+/// Called once at startup.
+pub fn start(app: &AppHandle) -> Result<(), String> {
+    let connection = Builder::session()
+        .and_then(|b| b.name(BUS_NAME))
+        .and_then(|b| b.serve_at(OBJECT_PATH, Service { app: app.clone() }))
+        .and_then(|b| b.build())
+        .map_err(|e| format!("Failed to register {} on the session bus: {}", BUS_NAME, e))?;
+
+    let emitter = SignalEmitter::new(connection.inner(), OBJECT_PATH)
+        .map_err(|e| e.to_string())?
+        .into_owned();
+
+    let (tx, rx) = mpsc::channel();
+    *SIGNALS.lock().unwrap() = Some(tx);
+
+    std::thread::spawn(move || {
+        // Owning the connection here keeps the bus name for the life of the app
+        let _connection = connection;
+        for signal in rx {
+            let result = zbus::block_on(async {
+                match signal {
+                    Signal::DeviceSwitched { id, name, group } => {
+                        Service::device_switched(&emitter, &id, &name, &group).await
+                    }
+                    Signal::DevicesChanged => Service::devices_changed(&emitter).await,
+                }
+            });
+            if let Err(e) = result {
+                eprintln!("Failed to emit D-Bus signal: {}", e);
+            }
+        }
+    });
+
+    app.listen_any("device-switched", |event| {
+        let payload: serde_json::Value = match serde_json::from_str(event.payload()) {
+            Ok(payload) => payload,
+            Err(_) => return,
+        };
+        let field = |value: &serde_json::Value| value.as_str().unwrap_or_default().to_string();
+
+        send(Signal::DeviceSwitched {
+            id: field(&payload["device"]["id"]),
+            name: payload["device"]["alias"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| field(&payload["device"]["name"])),
+            group: field(&payload["group_name"]),
+        });
+    });
+    app.listen_any("devices-changed", |_| send(Signal::DevicesChanged));
+
+    Ok(())
+}
+
+fn send(signal: Signal) {
+    if let Some(tx) = SIGNALS.lock().unwrap().as_ref() {
+        let _ = tx.send(signal);
+    }
+}
+
+fn summary(device: AudioDevice) -> (String, String) {
+    let name = device.display_name().to_string();
+    (device.id, name)
+}
@@ -0,0 +1,260 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+use crate::commands;
+use crate::state::{AppState, SwitchSource};
+use crate::switcher;
+
+pub const SCHEME: &str = "soundshift";
+
+// Longest group or device name a link may carry
+const MAX_VALUE_LEN: usize = 256;
+
+// What a link asks for, before names are looked up
+enum Link {
+    Cycle { group: String },
+    SelectDevice { device: String },
+    // Index of the entry, from a position counting from 1
+    SelectMember { group: String, index: usize },
+}
+
+// A link resolved against current state, ready to confirm and run
+struct Switch {
+    description: String,
+    kind: SwitchKind,
+}
+
+enum SwitchKind {
+    Cycle { group_id: String },
+    Device { device_id: String },
+    Member { group_id: String, index: usize },
+}
+
+/// Follows a `soundshift://` link, asking first unless links are trusted in settings.
+pub fn handle(app: &AppHandle, url: &str) {
+    let settings = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.settings.deep_links.clone()
+    };
+
+    if !settings.enabled {
+        eprintln!("Ignoring link {}: links are turned off in settings", url);
+        return;
+    }
+
+    let switch = match parse(url).and_then(|link| resolve(app, link)) {
+        Ok(switch) => switch,
+        Err(e) => {
+            eprintln!("Ignoring link {}: {}", url, e);
+            return;
+        }
+    };
+
+    if !settings.confirm {
+        run(app, switch.kind);
+        return;
+    }
+
+    // Links can come from any web page or document, so let the user see what
+    // one is about to do
+    let handle = app.clone();
+    app.dialog()
+        .message(format!("A link wants to {}.", switch.description))
+        .title("SoundShift")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Switch".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            if confirmed {
+                run(&handle, switch.kind);
+            }
+        });
+}
+
+// Accepts `soundshift://cycle?group=<group>`, `soundshift://select?device=<device>`
+// and `soundshift://select?group=<group>&position=<position>`, where positions
+// count from 1
+fn parse(url: &str) -> Result<Link, String> {
+    let url = Url::parse(url.trim()).map_err(|e| format!("invalid URL ({})", e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("not a {}:// link", SCHEME));
+    }
+    if !matches!(url.path(), "" | "/") || url.fragment().is_some() {
+        return Err("unexpected path".to_string());
+    }
+
+    let mut group = None;
+    let mut device = None;
+    let mut position = None;
+    for (key, value) in url.query_pairs() {
+        if value.trim().is_empty()
+            || value.len() > MAX_VALUE_LEN
+            || value.chars().any(char::is_control)
+        {
+            return Err(format!("invalid value for {}", key));
+        }
+
+        let slot = match key.as_ref() {
+            "group" => &mut group,
+            "device" => &mut device,
+            "position" => &mut position,
+            _ => return Err(format!("unknown parameter {}", key)),
+        };
+        if slot.replace(value.trim().to_string()).is_some() {
+            return Err(format!("{} given more than once", key));
+        }
+    }
+
+    let action = url.host_str().unwrap_or_default().to_lowercase();
+    match (action.as_str(), group, device, position) {
+        ("cycle", Some(group), None, None) => Ok(Link::Cycle { group }),
+        ("select", None, Some(device), None) => Ok(Link::SelectDevice { device }),
+        ("select", Some(group), None, Some(position)) => position
+            .parse()
+            .map_err(|_| format!("invalid device position {}", position))
+            .and_then(switcher::position_index)
+            .map(|index| Link::SelectMember { group, index }),
+        ("cycle", ..) => Err("cycle needs a group and nothing else".to_string()),
+        ("select", ..) => Err("select needs a device, or a group and a position".to_string()),
+        _ => Err(format!("unknown action {}", action)),
+    }
+}
+
+fn resolve(app: &AppHandle, link: Link) -> Result<Switch, String> {
+    match link {
+        Link::Cycle { group } => {
+            let (group_id, name) = find_group(app, &group)?;
+            Ok(Switch {
+                description: format!("switch {} to its next device", name),
+                kind: SwitchKind::Cycle { group_id },
+            })
+        }
+        Link::SelectDevice { device } => {
+            let devices = commands::get_audio_devices(app.state())?;
+            let device = switcher::lookup_device(&devices, &device)?;
+            Ok(Switch {
+                description: format!("make {} the default output device", device.display_name()),
+                kind: SwitchKind::Device {
+                    device_id: device.id.clone(),
+                },
+            })
+        }
+        Link::SelectMember { group, index } => {
+            let state = app.state::<Mutex<AppState>>();
+            let state = state.lock().unwrap();
+            let group = switcher::find_group(&state.groups, &group)
+                .ok_or_else(|| format!("no group named {}", group))?;
+            let member = group
+                .devices
+                .get(index)
+                .ok_or_else(|| format!("{} has no device {}", group.name, index + 1))?;
+            let name = state.aliases.get(&member.id).unwrap_or(&member.name);
+
+            Ok(Switch {
+                description: format!("switch {} to {}", group.name, name),
+                kind: SwitchKind::Member {
+                    group_id: group.id.clone(),
+                    index,
+                },
+            })
+        }
+    }
+}
+
+// Switches on a thread of its own, as the dialog answers on the main thread
+fn run(app: &AppHandle, kind: SwitchKind) {
+    let app = app.clone();
+    std::thread::spawn(move || follow(&app, kind));
+}
+
+fn follow(app: &AppHandle, kind: SwitchKind) {
+    let result = match kind {
+        SwitchKind::Cycle { group_id } => switcher::cycle_group(app, &group_id, SwitchSource::Link),
+        SwitchKind::Device { device_id } => {
+            switcher::set_default_device(app, &device_id, SwitchSource::Link)
+        }
+        SwitchKind::Member { group_id, index } => {
+            switcher::select_group_device(app, &group_id, index, SwitchSource::Link)
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to follow link: {}", e);
+    }
+}
+
+fn find_group(app: &AppHandle, query: &str) -> Result<(String, String), String> {
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().unwrap();
+    switcher::find_group(&state.groups, query)
+        .map(|g| (g.id.clone(), g.name.clone()))
+        .ok_or_else(|| format!("no group named {}", query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cycle() {
+        assert!(matches!(
+            parse("soundshift://cycle?group=Desk%20Setup"),
+            Ok(Link::Cycle { group }) if group == "Desk Setup"
+        ));
+        // Query strings use + for spaces too
+        assert!(matches!(
+            parse("soundshift://CYCLE/?group=Desk+Setup"),
+            Ok(Link::Cycle { group }) if group == "Desk Setup"
+        ));
+    }
+
+    #[test]
+    fn parses_select_device() {
+        assert!(matches!(
+            parse("soundshift://select?device=Headphones"),
+            Ok(Link::SelectDevice { device }) if device == "Headphones"
+        ));
+    }
+
+    #[test]
+    fn positions_count_from_one() {
+        assert!(matches!(
+            parse("soundshift://select?group=Desk&position=1"),
+            Ok(Link::SelectMember { group, index: 0 }) if group == "Desk"
+        ));
+        assert!(matches!(
+            parse("soundshift://select?group=Desk&position=3"),
+            Ok(Link::SelectMember { index: 2, .. })
+        ));
+        assert!(parse("soundshift://select?group=Desk&position=0").is_err());
+        assert!(parse("soundshift://select?group=Desk&position=-1").is_err());
+        assert!(parse("soundshift://select?group=Desk&position=two").is_err());
+    }
+
+    #[test]
+    fn rejects_old_index_parameter() {
+        assert!(parse("soundshift://select?group=Desk&index=0").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        assert!(parse("https://cycle?group=Desk").is_err());
+        assert!(parse("soundshift://cycle/extra?group=Desk").is_err());
+        assert!(parse("soundshift://cycle?group=Desk#top").is_err());
+        assert!(parse("soundshift://cycle?group=Desk&group=Music").is_err());
+        assert!(parse("soundshift://cycle?group=%20").is_err());
+        assert!(parse("soundshift://cycle?group=Desk%0A").is_err());
+        assert!(parse("soundshift://cycle").is_err());
+        assert!(parse("soundshift://select?group=Desk&device=Headphones").is_err());
+        assert!(parse("soundshift://mute?device=Headphones").is_err());
+    }
+
+    #[test]
+    fn rejects_long_values() {
+        let long = "a".repeat(MAX_VALUE_LEN + 1);
+        assert!(parse(&format!("soundshift://cycle?group={}", long)).is_err());
+    }
+}
@@ -0,0 +1,417 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::state::{unix_timestamp, AppState, AudioDevice};
+
+// How often a running hook is checked for having exited
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// How long to keep reading output once a hook has exited. Programs it started
+// in the background can hold its output open indefinitely, so this is a
+// deadline rather than a gap between lines.
+const OUTPUT_GRACE: Duration = Duration::from_millis(500);
+
+// Runs kept for the log in Settings, newest first, and the output kept for each
+const LOG_SIZE: usize = 50;
+const MAX_OUTPUT_LINES: usize = 50;
+
+static LOG: Mutex<VecDeque<HookRun>> = Mutex::new(VecDeque::new());
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    PreSwitch,
+    PostSwitch,
+}
+
+impl Stage {
+    fn name(self) -> &'static str {
+        match self {
+            Stage::PreSwitch => "pre_switch",
+            Stage::PostSwitch => "post_switch",
+        }
+    }
+}
+
+// One hook command run for one switch
+#[derive(Debug, Clone, Serialize)]
+pub struct HookRun {
+    pub id: u64,
+    pub stage: Stage,
+    pub command: String,
+    // Device switched to, for telling runs apart in the log
+    pub device: String,
+    pub at: u64,
+    pub duration_ms: u64,
+    // Exit code, if the hook exited by itself with one
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    // Why the hook couldn't be started
+    pub error: Option<String>,
+    pub success: bool,
+    // Standard output and error, interleaved
+    pub output: Vec<String>,
+}
+
+/// The switch a hook runs for. `previous` is the default device beforehand,
+/// if there was one.
+pub struct SwitchInfo<'a> {
+    pub previous: Option<&'a AudioDevice>,
+    pub device: &'a AudioDevice,
+    pub group_id: Option<&'a str>,
+    pub group_name: Option<&'a str>,
+}
+
+/// Runs the global hook for `stage`, then the group's own. Pre-switch hooks
+/// finish (or time out) before returning so they can prepare for the switch;
+/// post-switch hooks run in the background.
+pub fn run(app: &AppHandle, stage: Stage, switch: &SwitchInfo) {
+    let (commands, timeout) = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        let group = switch
+            .group_id
+            .and_then(|id| state.groups.iter().find(|g| g.id == id));
+
+        let commands: Vec<String> = match stage {
+            Stage::PreSwitch => [
+                Some(&state.settings.hooks.pre_switch),
+                group.map(|g| &g.hooks.pre_switch),
+            ],
+            Stage::PostSwitch => [
+                Some(&state.settings.hooks.post_switch),
+                group.map(|g| &g.hooks.post_switch),
+            ],
+        }
+        .into_iter()
+        .flatten()
+        .filter(|c| !c.trim().is_empty())
+        .cloned()
+        .collect();
+
+        (
+            commands,
+            Duration::from_secs(state.settings.hooks.timeout_secs.max(1)),
+        )
+    };
+
+    if commands.is_empty() {
+        return;
+    }
+
+    let env = environment(stage, switch);
+    let device = switch.device.display_name().to_string();
+    let app = app.clone();
+    let run_all = move || {
+        for command in &commands {
+            let run = run_command(stage, command, &env, timeout, &device);
+            record(&app, run);
+        }
+    };
+
+    match stage {
+        Stage::PreSwitch => run_all(),
+        Stage::PostSwitch => {
+            std::thread::spawn(run_all);
+        }
+    }
+}
+
+fn environment(stage: Stage, switch: &SwitchInfo) -> Vec<(&'static str, String)> {
+    let text = |value: Option<&str>| value.unwrap_or_default().to_string();
+
+    vec![
+        ("SOUNDSHIFT_HOOK", stage.name().to_string()),
+        (
+            "SOUNDSHIFT_OLD_DEVICE_ID",
+            text(switch.previous.map(|d| d.id.as_str())),
+        ),
+        (
+            "SOUNDSHIFT_OLD_DEVICE_NAME",
+            text(switch.previous.map(|d| d.display_name())),
+        ),
+        ("SOUNDSHIFT_NEW_DEVICE_ID", switch.device.id.clone()),
+        (
+            "SOUNDSHIFT_NEW_DEVICE_NAME",
+            switch.device.display_name().to_string(),
+        ),
+        ("SOUNDSHIFT_GROUP_ID", text(switch.group_id)),
+        ("SOUNDSHIFT_GROUP_NAME", text(switch.group_name)),
+    ]
+}
+
+/// Recent hook runs, newest first.
+pub fn runs() -> Vec<HookRun> {
+    LOG.lock().unwrap().iter().cloned().collect()
+}
+
+fn run_command(
+    stage: Stage,
+    command: &str,
+    env: &[(&str, String)],
+    timeout: Duration,
+    device: &str,
+) -> HookRun {
+    let mut run = HookRun {
+        id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+        stage,
+        command: command.to_string(),
+        device: device.to_string(),
+        at: unix_timestamp(),
+        duration_ms: 0,
+        exit_code: None,
+        timed_out: false,
+        error: None,
+        success: false,
+        output: Vec::new(),
+    };
+    let started = Instant::now();
+
+    let mut child = match shell(command)
+        .envs(env.iter().cloned())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            run.error = Some(e.to_string());
+            return run;
+        }
+    };
+
+    let tree = ProcessTree::new(&child);
+
+    // Read output as it comes, so a chatty hook can't fill the pipe and stall
+    let (tx, rx) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        read_lines(stdout, tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        read_lines(stderr, tx);
+    }
+
+    let status = wait(&mut child, &tree, timeout);
+    run.duration_ms = started.elapsed().as_millis() as u64;
+
+    // Readers still blocked on a pipe held open by a background program end
+    // on their next line, once the channel is gone
+    let deadline = Instant::now() + OUTPUT_GRACE;
+    let mut skipped = 0;
+    while let Ok(line) = rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        if run.output.len() < MAX_OUTPUT_LINES {
+            run.output.push(line);
+        } else {
+            skipped += 1;
+        }
+    }
+    drop(rx);
+    if skipped > 0 {
+        run.output.push(format!("({} more lines)", skipped));
+    }
+
+    match status {
+        Some(status) => {
+            run.exit_code = status.code();
+            run.success = status.success();
+        }
+        None => run.timed_out = true,
+    }
+    run
+}
+
+// Adds a finished run to the log, and to the console for development builds
+fn record(app: &AppHandle, run: HookRun) {
+    #[cfg(debug_assertions)]
+    {
+        let outcome = match (&run.error, run.exit_code) {
+            (Some(e), _) => format!("couldn't start: {}", e),
+            _ if run.timed_out => "timed out and was killed".to_string(),
+            _ if run.success => "finished".to_string(),
+            (None, Some(code)) => format!("failed with exit code {}", code),
+            (None, None) => "failed".to_string(),
+        };
+        eprintln!("{} hook `{}` {}", run.stage.name(), run.command, outcome);
+        for line in &run.output {
+            eprintln!("  {}", line);
+        }
+    }
+
+    {
+        let mut log = LOG.lock().unwrap();
+        log.push_front(run.clone());
+        log.truncate(LOG_SIZE);
+    }
+
+    let _ = app.emit("hook-run", run);
+}
+
+// Waits for the hook to exit, killing it and everything it started once the
+// timeout passes
+fn wait(
+    child: &mut Child,
+    tree: &ProcessTree,
+    timeout: Duration,
+) -> Option<std::process::ExitStatus> {
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Some(status),
+            Ok(None) if started.elapsed() < timeout => std::thread::sleep(POLL_INTERVAL),
+            _ => {
+                tree.kill();
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
+fn read_lines<R: Read + Send + 'static>(pipe: R, tx: Sender<String>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(pipe).split(b'\n').map_while(Result::ok) {
+            let line = String::from_utf8_lossy(&line).trim_end().to_string();
+            if tx.send(line).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+// The hook's shell and whatever it runs, so a timeout kills the lot rather than
+// leaving the real command running with the output pipes open
+#[cfg(unix)]
+struct ProcessTree {
+    group: libc::pid_t,
+}
+
+#[cfg(unix)]
+impl ProcessTree {
+    // The shell leads a process group of its own, see `shell`
+    fn new(child: &Child) -> Self {
+        ProcessTree {
+            group: child.id() as libc::pid_t,
+        }
+    }
+
+    fn kill(&self) {
+        unsafe {
+            libc::killpg(self.group, libc::SIGKILL);
+        }
+    }
+}
+
+// Windows has no process groups, so the hook goes in a job object, which takes
+// in everything it starts. Processes it started before joining can escape.
+#[cfg(windows)]
+struct ProcessTree {
+    job: Option<windows::Win32::Foundation::HANDLE>,
+}
+
+#[cfg(windows)]
+impl ProcessTree {
+    fn new(child: &Child) -> Self {
+        use std::os::windows::io::AsRawHandle;
+        use windows::core::PCWSTR;
+        use windows::Win32::Foundation::{CloseHandle, HANDLE};
+        use windows::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW};
+
+        let job = unsafe {
+            CreateJobObjectW(None, PCWSTR::null()).ok().and_then(|job| {
+                // Should SoundShift go away mid-hook, closing the job kills it
+                if set_kill_on_close(job, true)
+                    .and_then(|_| AssignProcessToJobObject(job, HANDLE(child.as_raw_handle())))
+                    .is_ok()
+                {
+                    Some(job)
+                } else {
+                    let _ = CloseHandle(job);
+                    None
+                }
+            })
+        };
+        ProcessTree { job }
+    }
+
+    fn kill(&self) {
+        use windows::Win32::System::JobObjects::TerminateJobObject;
+
+        if let Some(job) = self.job {
+            unsafe {
+                let _ = TerminateJobObject(job, 1);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ProcessTree {
+    // Programs a hook leaves running on purpose, like an app it restarts, keep
+    // running once it's done
+    fn drop(&mut self) {
+        use windows::Win32::Foundation::CloseHandle;
+
+        if let Some(job) = self.job.take() {
+            unsafe {
+                let _ = set_kill_on_close(job, false);
+                let _ = CloseHandle(job);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+unsafe fn set_kill_on_close(
+    job: windows::Win32::Foundation::HANDLE,
+    kill: bool,
+) -> windows::core::Result<()> {
+    use windows::Win32::System::JobObjects::{
+        JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+    if kill {
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+    }
+    SetInformationJobObject(
+        job,
+        JobObjectExtendedLimitInformation,
+        &info as *const _ as *const std::ffi::c_void,
+        std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+    )
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+    // Keep a console window from flashing up for every hook
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let mut shell = Command::new("cmd");
+    shell
+        .arg("/C")
+        .raw_arg(command)
+        .creation_flags(CREATE_NO_WINDOW);
+    shell
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    use std::os::unix::process::CommandExt;
+
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command).process_group(0);
+    shell
+}
@@ -0,0 +1,458 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+use uuid::Uuid;
+
+use crate::commands;
+use crate::state::{AppState, SwitchSource};
+use crate::switcher;
+
+// Events relayed to `/api/events` subscribers
+const STREAMED_EVENTS: &[&str] = &["device-switched", "devices-changed"];
+
+// Comments sent on idle event streams, which also reveal closed connections
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+// Requests handled at once, event streams included; more get a 503
+const MAX_CONNECTIONS: usize = 16;
+
+// Largest request body read; the only body is a device ID
+const MAX_BODY: u64 = 64 * 1024;
+
+// Running server and the port it's bound to
+static SERVER: Mutex<Option<(u16, Arc<Server>)>> = Mutex::new(None);
+
+// One sender per open event stream
+static SUBSCRIBERS: Mutex<Vec<Sender<String>>> = Mutex::new(Vec::new());
+
+// Requests being handled right now
+static CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+// Counts a request as handled once dropped
+struct Connection;
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        CONNECTIONS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Deserialize)]
+struct SwitchBody {
+    device_id: String,
+}
+
+/// Forwards app events to event stream subscribers. Called once at startup.
+pub fn start_event_relay(app: &AppHandle) {
+    for event in STREAMED_EVENTS {
+        app.listen_any(*event, move |e| {
+            let message = format!("event: {}\ndata: {}\n\n", event, e.payload());
+            SUBSCRIBERS
+                .lock()
+                .unwrap()
+                .retain(|tx| tx.send(message.clone()).is_ok());
+        });
+    }
+}
+
+/// Starts, stops or moves the server to match the current settings.
+pub fn sync(app: &AppHandle) {
+    let state = app.state::<Mutex<AppState>>();
+    let (settings, generated_token) = {
+        let mut state = state.lock().unwrap();
+        let settings = &mut state.settings.http_api;
+
+        // Never serve without a token
+        let generate = settings.enabled && settings.token.is_empty();
+        if generate {
+            settings.token = Uuid::new_v4().simple().to_string();
+        }
+        (settings.clone(), generate)
+    };
+
+    if generated_token {
+        commands::save_settings(app, &state);
+    }
+
+    let mut server = SERVER.lock().unwrap();
+    if let Some((port, _)) = *server {
+        if settings.enabled && port == settings.port {
+            return;
+        }
+    }
+
+    if let Some((_, running)) = server.take() {
+        running.unblock();
+        // Dropping the senders ends the open event streams
+        SUBSCRIBERS.lock().unwrap().clear();
+    }
+
+    if !settings.enabled {
+        return;
+    }
+
+    // Only reachable from this machine
+    match Server::http(("127.0.0.1", settings.port)) {
+        Ok(running) => {
+            let running = Arc::new(running);
+            *server = Some((settings.port, running.clone()));
+
+            let app = app.clone();
+            std::thread::spawn(move || {
+                for request in running.incoming_requests() {
+                    if CONNECTIONS.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                        CONNECTIONS.fetch_sub(1, Ordering::SeqCst);
+                        respond(
+                            request,
+                            Err((503, "Too many connections".to_string())),
+                            None,
+                        );
+                        continue;
+                    }
+
+                    let app = app.clone();
+                    // Event streams stay open, so give every request its own thread
+                    std::thread::spawn(move || {
+                        let _connection = Connection;
+                        handle(&app, request);
+                    });
+                }
+            });
+        }
+        Err(e) => eprintln!("Failed to start HTTP API on port {}: {}", settings.port, e),
+    }
+}
+
+fn handle(app: &AppHandle, mut request: Request) {
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+
+    // Browsers say which page is calling; other clients send no origin
+    let origin = match allowed_origin(app, &request) {
+        Ok(origin) => origin,
+        Err(origin) => {
+            let message = format!("Origin {} is not allowed", origin);
+            respond(request, Err((403, message)), None);
+            return;
+        }
+    };
+    let origin = origin.as_deref();
+
+    // CORS preflight for browser dashboards; carries no token
+    if method == Method::Options {
+        let mut response = Response::empty(204)
+            .with_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"))
+            .with_header(header(
+                "Access-Control-Allow-Headers",
+                "Authorization, Content-Type",
+            ));
+        for header in cors_headers(origin) {
+            response.add_header(header);
+        }
+        let _ = request.respond(response);
+        return;
+    }
+
+    if !authorized(app, &request, query) {
+        respond(
+            request,
+            Err((401, "Missing or invalid token".to_string())),
+            origin,
+        );
+        return;
+    }
+
+    let segments: Vec<String> = path
+        .trim_matches('/')
+        .split('/')
+        .map(percent_decode)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let state = app.state::<Mutex<AppState>>();
+
+    let result = match (&method, segments.as_slice()) {
+        (Method::Get, ["api", "events"]) => {
+            stream_events(request, origin);
+            return;
+        }
+        (Method::Get, ["api", "devices"]) => commands::get_audio_devices(state)
+            .map(|devices| json!(devices))
+            .map_err(|e| (500, e)),
+        (Method::Get, ["api", "devices", "default"]) => commands::get_audio_devices(state)
+            .map_err(|e| (500, e))
+            .and_then(|devices| {
+                devices
+                    .into_iter()
+                    .find(|d| d.is_default)
+                    .map(|d| json!(d))
+                    .ok_or((404, "No default output device".to_string()))
+            }),
+        (Method::Post, ["api", "switch"]) => read_body(&mut request).and_then(|body| {
+            match serde_json::from_str::<SwitchBody>(&body) {
+                Ok(body) => {
+                    switcher::set_default_device(app, &body.device_id, SwitchSource::HttpApi)
+                        .map(|device| json!(device))
+                        .map_err(|e| (409, e))
+                }
+                Err(e) => Err((400, format!("Invalid body: {}", e))),
+            }
+        }),
+        (Method::Get, ["api", "groups"]) => Ok(json!(commands::get_groups(state))),
+        (Method::Post, ["api", "groups", group, "cycle"]) => find_group_id(app, group)
+            .and_then(|id| {
+                switcher::cycle_group(app, &id, SwitchSource::HttpApi).map_err(|e| (409, e))
+            })
+            .map(|device| json!(device)),
+        (Method::Post, ["api", "groups", group, "select", position]) => position
+            .parse()
+            .map_err(|_| format!("Invalid device position {}", position))
+            .and_then(switcher::position_index)
+            .map_err(|e| (400, e))
+            .and_then(|index| {
+                find_group_id(app, group).and_then(|id| {
+                    switcher::select_group_device(app, &id, index, SwitchSource::HttpApi)
+                        .map_err(|e| (409, e))
+                })
+            })
+            .map(|device| json!(device)),
+        _ => Err((404, "Not found".to_string())),
+    };
+
+    respond(request, result, origin);
+}
+
+// Reads a request body of up to MAX_BODY bytes
+fn read_body(request: &mut Request) -> Result<String, (u16, String)> {
+    let too_large = (413, format!("Body is larger than {} bytes", MAX_BODY));
+    if request
+        .body_length()
+        .map_or(false, |length| length as u64 > MAX_BODY)
+    {
+        return Err(too_large);
+    }
+
+    // One byte over the limit is enough to tell the body is too large
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| (400, format!("Couldn't read body: {}", e)))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err(too_large);
+    }
+    Ok(body)
+}
+
+// The request's origin if it's one the user allowed, or no origin for clients
+// that aren't browsers. Errs with the origin of any other page.
+fn allowed_origin(app: &AppHandle, request: &Request) -> Result<Option<String>, String> {
+    let origin = match request.headers().iter().find(|h| h.field.equiv("Origin")) {
+        Some(h) => h.value.as_str().trim_end_matches('/').to_string(),
+        None => return Ok(None),
+    };
+
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().unwrap();
+    let allowed = state
+        .settings
+        .http_api
+        .allowed_origins
+        .iter()
+        .any(|a| a.trim().trim_end_matches('/').eq_ignore_ascii_case(&origin));
+
+    if allowed {
+        Ok(Some(origin))
+    } else {
+        Err(origin)
+    }
+}
+
+fn cors_headers(origin: Option<&str>) -> Vec<Header> {
+    match origin {
+        Some(origin) => vec![
+            header("Access-Control-Allow-Origin", origin),
+            header("Vary", "Origin"),
+        ],
+        None => Vec::new(),
+    }
+}
+
+// Accepts `Authorization: Bearer <token>`, or `?token=` for clients such as
+// EventSource that can't set headers
+fn authorized(app: &AppHandle, request: &Request, query: &str) -> bool {
+    let token = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.settings.http_api.token.clone()
+    };
+    if token.is_empty() {
+        return false;
+    }
+
+    let from_header = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string());
+    let from_query = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "token")
+        .map(|(_, value)| percent_decode(value));
+
+    from_header
+        .or(from_query)
+        .map_or(false, |given| same_token(&given, &token))
+}
+
+// Looks at every byte whatever the first difference, so how long a wrong
+// token takes to reject doesn't reveal how much of it was right
+fn same_token(given: &str, token: &str) -> bool {
+    let difference = given
+        .bytes()
+        .zip(token.bytes())
+        .fold(0, |difference, (a, b)| difference | (a ^ b));
+    given.len() == token.len() && std::hint::black_box(difference) == 0
+}
+
+fn stream_events(request: Request, origin: Option<&str>) {
+    let (tx, rx) = mpsc::channel();
+    SUBSCRIBERS.lock().unwrap().push(tx);
+
+    // Write the response by hand: tiny_http buffers chunked bodies, which would
+    // hold events back
+    let mut writer = request.into_writer();
+    let mut head = "HTTP/1.1 200 OK\r\n\
+                    Content-Type: text/event-stream\r\n\
+                    Cache-Control: no-cache\r\n\
+                    Connection: close\r\n"
+        .to_string();
+    for header in cors_headers(origin) {
+        head.push_str(&format!("{}: {}\r\n", header.field, header.value));
+    }
+    head.push_str("\r\n");
+    if writer
+        .write_all(head.as_bytes())
+        .and_then(|_| writer.flush())
+        .is_err()
+    {
+        return;
+    }
+
+    loop {
+        let message = match rx.recv_timeout(KEEPALIVE_INTERVAL) {
+            Ok(message) => message,
+            Err(RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        if writer
+            .write_all(message.as_bytes())
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+fn find_group_id(app: &AppHandle, query: &str) -> Result<String, (u16, String)> {
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().unwrap();
+    switcher::find_group(&state.groups, query)
+        .map(|g| g.id.clone())
+        .ok_or_else(|| (404, format!("No group named {}", query)))
+}
+
+fn respond(request: Request, result: Result<Value, (u16, String)>, origin: Option<&str>) {
+    let (status, body) = match result {
+        Ok(value) => (200, value),
+        Err((status, message)) => (status, json!({ "error": message })),
+    };
+
+    let mut response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"));
+    for header in cors_headers(origin) {
+        response.add_header(header);
+    }
+    let _ = request.respond(response);
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).unwrap()
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_escapes() {
+        assert_eq!(percent_decode("Desk%20Speakers"), "Desk Speakers");
+        assert_eq!(percent_decode("%7B0.0.0%7D"), "{0.0.0}");
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+    }
+
+    #[test]
+    fn keeps_plus_signs() {
+        // Only form bodies use + for spaces, not paths or our query parameters
+        assert_eq!(percent_decode("Speakers+2"), "Speakers+2");
+        assert_eq!(percent_decode("a%2Bb"), "a+b");
+    }
+
+    #[test]
+    fn keeps_invalid_escapes() {
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("%4"), "%4");
+        assert_eq!(percent_decode("%%41"), "%A");
+    }
+
+    #[test]
+    fn replaces_invalid_utf8() {
+        assert_eq!(percent_decode("%FF"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn compares_tokens() {
+        assert!(same_token("secret", "secret"));
+        assert!(!same_token("secreT", "secret"));
+        assert!(!same_token("secret2", "secret"));
+        assert!(!same_token("", "secret"));
+    }
+}
@@ -0,0 +1,385 @@
+use regex::{Regex, RegexBuilder};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::state::{
+    unix_timestamp, AudioDevice, DeviceGroup, DeviceMatcher, DeviceRelink, GroupDevice, MatchKind,
+};
+
+// Only keep the most recent re-links per group
+const MAX_RELINKS: usize = 20;
+
+// Regex matchers by pattern, compiled once rather than for every device checked
+static REGEXES: Mutex<BTreeMap<String, Regex>> = Mutex::new(BTreeMap::new());
+
+/// Finds the live device a group entry refers to. Endpoint IDs change when a USB
+/// device moves to another port, so fall back to the same name in the hardware
+/// container, then the friendly name, then the entry's user-defined name pattern.
+/// Devices in `claimed` already belong to other entries and are never matched by
+/// a fallback.
+pub fn find_device<'a>(
+    entry: &GroupDevice,
+    online: &'a [AudioDevice],
+    claimed: &[&str],
+) -> Option<(&'a AudioDevice, MatchKind)> {
+    if let Some(device) = online.iter().find(|d| d.id == entry.id) {
+        return Some((device, MatchKind::Id));
+    }
+
+    let candidates: Vec<&AudioDevice> = online
+        .iter()
+        .filter(|d| !claimed.contains(&d.id.as_str()))
+        .collect();
+
+    // A container can hold several endpoints (e.g. a headset's stereo and
+    // hands-free outputs, or a dock's speakers and S/PDIF), so the name has to
+    // match too; re-links are saved, and a different endpoint would stick
+    if let Some(ref container_id) = entry.container_id {
+        if let Some(device) = candidates
+            .iter()
+            .find(|d| d.container_id.as_ref() == Some(container_id) && d.name == entry.name)
+        {
+            return Some((device, MatchKind::Container));
+        }
+    }
+
+    // Names are only trusted when unambiguous, e.g. not two identical DACs
+    let same_name: Vec<&AudioDevice> = candidates
+        .iter()
+        .copied()
+        .filter(|d| d.name == entry.name)
+        .collect();
+    if let [device] = same_name.as_slice() {
+        return Some((device, MatchKind::Name));
+    }
+
+    if let Some(ref pattern) = entry.match_pattern {
+        if let Some(device) = candidates.iter().find(|d| pattern.matches(&d.name)) {
+            return Some((device, MatchKind::Pattern));
+        }
+    }
+
+    None
+}
+
+/// Points group entries whose device came back under a new ID at that ID, and
+/// records each re-link in the group. Returns the re-links made.
+pub fn relink_group(group: &mut DeviceGroup, online: &[AudioDevice]) -> Vec<DeviceRelink> {
+    let mut relinks = Vec::new();
+
+    for i in 0..group.devices.len() {
+        // Pattern entries have no fixed identity to re-link
+        if group.devices[i].matcher.is_some() {
+            continue;
+        }
+
+        // Entries that still match exactly keep their devices to themselves
+        let claimed: Vec<&str> = group
+            .devices
+            .iter()
+            .enumerate()
+            .filter(|(j, d)| *j != i && online.iter().any(|o| o.id == d.id))
+            .map(|(_, d)| d.id.as_str())
+            .collect();
+
+        let (device, kind) = match find_device(&group.devices[i], online, &claimed) {
+            Some((device, kind)) if kind != MatchKind::Id => (device.clone(), kind),
+            _ => continue,
+        };
+
+        let entry = &mut group.devices[i];
+        relinks.push(DeviceRelink {
+            old_id: entry.id.clone(),
+            new_id: device.id.clone(),
+            name: device.name.clone(),
+            matched_by: kind,
+            at: unix_timestamp(),
+        });
+
+        entry.id = device.id;
+        entry.name = device.name;
+        if device.container_id.is_some() {
+            entry.container_id = device.container_id;
+        }
+    }
+
+    group.relinks.extend(relinks.iter().cloned());
+    if group.relinks.len() > MAX_RELINKS {
+        let excess = group.relinks.len() - MAX_RELINKS;
+        group.relinks.drain(..excess);
+    }
+
+    relinks
+}
+
+/// Resolves a group's entries against the live device list, in cycle order.
+/// Fixed entries contribute their device if online; pattern entries contribute
+/// every matching device not already listed. Each member carries its entry index.
+pub fn resolve_members<'a>(
+    group: &DeviceGroup,
+    online: &'a [AudioDevice],
+) -> Vec<(usize, &'a AudioDevice)> {
+    let mut members: Vec<(usize, &AudioDevice)> = Vec::new();
+
+    // Fixed entries claim their devices first, so a pattern listed before one
+    // can't leave it looking offline
+    let (fixed, patterns): (Vec<_>, Vec<_>) = group
+        .devices
+        .iter()
+        .enumerate()
+        .partition(|(_, entry)| entry.matcher.is_none());
+
+    for (i, entry) in fixed.into_iter().chain(patterns) {
+        let matches: Vec<&AudioDevice> = match entry.matcher {
+            Some(ref matcher) => online.iter().filter(|d| matcher.matches(&d.name)).collect(),
+            None => online.iter().filter(|d| d.id == entry.id).collect(),
+        };
+
+        for device in matches {
+            if !members.iter().any(|(_, m)| m.id == device.id) {
+                members.push((i, device));
+            }
+        }
+    }
+
+    // Back into cycle order; the sort is stable, so a pattern's devices keep
+    // the order they're listed in
+    members.sort_by_key(|(i, _)| *i);
+    members
+}
+
+impl DeviceMatcher {
+    pub fn pattern(&self) -> &str {
+        match self {
+            DeviceMatcher::Wildcard(pattern) | DeviceMatcher::Regex(pattern) => pattern,
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            DeviceMatcher::Wildcard(pattern) => wildcard_match(pattern, name),
+            // Invalid patterns are rejected when saved, so just match nothing here
+            DeviceMatcher::Regex(pattern) => {
+                compiled(pattern).map_or(false, |re| re.is_match(name))
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            DeviceMatcher::Wildcard(pattern) if pattern.trim().is_empty() => {
+                Err("Device pattern is empty".to_string())
+            }
+            DeviceMatcher::Wildcard(_) => Ok(()),
+            DeviceMatcher::Regex(pattern) => compiled(pattern).map(|_| ()),
+        }
+    }
+}
+
+// The case-insensitive regex for `pattern`, from the cache if it's been built
+fn compiled(pattern: &str) -> Result<Regex, String> {
+    let mut regexes = REGEXES.lock().unwrap();
+    if let Some(re) = regexes.get(pattern) {
+        return Ok(re.clone());
+    }
+
+    let re = RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| format!("Invalid device regex {}: {}", pattern, e))?;
+    regexes.insert(pattern.to_string(), re.clone());
+    Ok(re)
+}
+
+/// Checks every pattern entry and re-link pattern in a group.
+pub fn validate_group(group: &DeviceGroup) -> Result<(), String> {
+    group
+        .devices
+        .iter()
+        .flat_map(|d| d.matcher.iter().chain(d.match_pattern.iter()))
+        .try_for_each(DeviceMatcher::validate)
+}
+
+/// Case-insensitive glob match supporting `*` (any run of characters) and `?`
+/// (any single character).
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{DeviceKind, GroupHooks};
+
+    fn device(id: &str, name: &str) -> AudioDevice {
+        AudioDevice {
+            id: id.to_string(),
+            name: name.to_string(),
+            is_default: false,
+            container_id: None,
+            alias: None,
+            kind: DeviceKind::Other,
+        }
+    }
+
+    fn fixed(id: &str, name: &str) -> GroupDevice {
+        GroupDevice {
+            id: id.to_string(),
+            name: name.to_string(),
+            container_id: None,
+            match_pattern: None,
+            matcher: None,
+        }
+    }
+
+    fn pattern(pattern: &str) -> GroupDevice {
+        GroupDevice {
+            matcher: Some(DeviceMatcher::Wildcard(pattern.to_string())),
+            ..fixed("", pattern)
+        }
+    }
+
+    fn group(devices: Vec<GroupDevice>) -> DeviceGroup {
+        DeviceGroup {
+            id: "group".to_string(),
+            name: "Desk".to_string(),
+            devices,
+            shortcut: None,
+            current_index: 0,
+            current_device_id: None,
+            relinks: Vec::new(),
+            hooks: GroupHooks::default(),
+            hide_notifications: false,
+        }
+    }
+
+    fn members<'a>(group: &DeviceGroup, online: &'a [AudioDevice]) -> Vec<(usize, &'a str)> {
+        resolve_members(group, online)
+            .into_iter()
+            .map(|(i, d)| (i, d.id.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn fixed_entry_keeps_device_from_earlier_pattern() {
+        let online = [
+            device("a", "Dock Speakers"),
+            device("b", "Dock S/PDIF"),
+            device("c", "Headphones"),
+        ];
+        let group = group(vec![
+            pattern("Dock*"),
+            fixed("c", "Headphones"),
+            fixed("b", "Dock S/PDIF"),
+        ]);
+
+        assert_eq!(members(&group, &online), [(0, "a"), (1, "c"), (2, "b")]);
+    }
+
+    #[test]
+    fn container_match_needs_the_same_name() {
+        let in_container = |id: &str, name: &str| AudioDevice {
+            container_id: Some("headset".to_string()),
+            ..device(id, name)
+        };
+        let entry = GroupDevice {
+            container_id: Some("headset".to_string()),
+            ..fixed("old", "Headphones (WH-1000XM4)")
+        };
+
+        // Only the hands-free endpoint is left, which mustn't stand in for stereo
+        let online = [in_container("hf", "Headset (WH-1000XM4 Hands-Free)")];
+        assert!(find_device(&entry, &online, &[]).is_none());
+
+        let online = [
+            in_container("hf", "Headset (WH-1000XM4 Hands-Free)"),
+            in_container("new", "Headphones (WH-1000XM4)"),
+        ];
+        assert!(matches!(
+            find_device(&entry, &online, &[]),
+            Some((device, MatchKind::Container)) if device.id == "new"
+        ));
+    }
+
+    #[test]
+    fn pattern_lists_each_device_once_in_order() {
+        let online = [
+            device("a", "Dock Speakers"),
+            device("b", "Headphones"),
+            device("c", "Dock S/PDIF"),
+        ];
+        let group = group(vec![pattern("Dock*"), pattern("*S/PDIF")]);
+
+        assert_eq!(members(&group, &online), [(0, "a"), (0, "c")]);
+    }
+
+    #[test]
+    fn wildcard_star_at_start() {
+        assert!(wildcard_match("*HDMI", "LG TV HDMI"));
+        assert!(wildcard_match("*HDMI", "HDMI"));
+        assert!(!wildcard_match("*HDMI", "HDMI Output"));
+    }
+
+    #[test]
+    fn wildcard_star_at_end() {
+        assert!(wildcard_match("Dock*", "Dock Speakers"));
+        assert!(wildcard_match("Dock*", "Dock"));
+        assert!(!wildcard_match("Dock*", "USB Dock"));
+    }
+
+    #[test]
+    fn wildcard_star_at_both_ends() {
+        assert!(wildcard_match("*Scarlett*", "Focusrite Scarlett 2i2"));
+        assert!(wildcard_match("*Scarlett*", "Scarlett"));
+        assert!(!wildcard_match("*Scarlett*", "Speakers (Realtek)"));
+    }
+
+    #[test]
+    fn wildcard_star_in_middle_backtracks() {
+        assert!(wildcard_match("Speakers*Audio", "Speakers USB Audio Audio"));
+        assert!(!wildcard_match("Speakers*Audio", "Speakers USB Audio 2"));
+    }
+
+    #[test]
+    fn wildcard_question_mark_is_one_character() {
+        assert!(wildcard_match("Headset ?", "Headset 2"));
+        assert!(!wildcard_match("Headset ?", "Headset "));
+        assert!(!wildcard_match("Headset ?", "Headset 12"));
+    }
+
+    #[test]
+    fn wildcard_ignores_case() {
+        assert!(wildcard_match("*hdmi*", "NVIDIA HDMI Output"));
+    }
+
+    #[test]
+    fn wildcard_without_stars_matches_whole_name() {
+        assert!(wildcard_match("Speakers", "Speakers"));
+        assert!(!wildcard_match("Speakers", "Speakers 2"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("", "Speakers"));
+    }
+}
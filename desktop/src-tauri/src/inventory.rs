@@ -0,0 +1,94 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::paths;
+use crate::state::{unix_timestamp, AppState, AudioDevice, InventoryEntry};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Stamps every online device as seen now, adds devices seen for the first time,
+/// and closes out devices that have disappeared since the last scan.
+pub fn record(app: &AppHandle, online: &[AudioDevice]) {
+    let now = unix_timestamp();
+    let state = app.state::<Mutex<AppState>>();
+    let mut state = state.lock().unwrap();
+
+    for entry in state.inventory.values_mut() {
+        if entry.connected && !online.iter().any(|d| d.id == entry.id) {
+            entry.connected = false;
+            entry.last_seen = now;
+        }
+    }
+
+    for device in online {
+        let entry = state
+            .inventory
+            .entry(device.id.clone())
+            .or_insert_with(|| InventoryEntry {
+                id: device.id.clone(),
+                last_known_name: device.name.clone(),
+                container_id: None,
+                first_seen: now,
+                last_seen: now,
+                connected: true,
+            });
+
+        entry.last_known_name = device.name.clone();
+        if device.container_id.is_some() {
+            entry.container_id = device.container_id.clone();
+        }
+        entry.last_seen = now;
+        entry.connected = true;
+    }
+
+    save(app, &state);
+}
+
+/// Forgets disconnected devices not seen for `older_than_days`. Devices still
+/// referenced by a group are kept so their members keep their history.
+pub fn prune(app: &AppHandle, older_than_days: u64) -> usize {
+    let cutoff = unix_timestamp().saturating_sub(older_than_days * SECONDS_PER_DAY);
+    let state = app.state::<Mutex<AppState>>();
+    let mut state = state.lock().unwrap();
+
+    let in_groups: Vec<String> = state
+        .groups
+        .iter()
+        .flat_map(|g| g.devices.iter().map(|d| d.id.clone()))
+        .collect();
+
+    let before = state.inventory.len();
+    state
+        .inventory
+        .retain(|id, entry| entry.connected || entry.last_seen >= cutoff || in_groups.contains(id));
+    let removed = before - state.inventory.len();
+
+    if removed > 0 {
+        save(app, &state);
+    }
+
+    removed
+}
+
+/// Removes a single device from the inventory.
+pub fn forget(app: &AppHandle, device_id: &str) -> Result<(), String> {
+    let state = app.state::<Mutex<AppState>>();
+    let mut state = state.lock().unwrap();
+
+    state
+        .inventory
+        .remove(device_id)
+        .ok_or("Device not found in inventory")?;
+
+    save(app, &state);
+
+    Ok(())
+}
+
+fn save(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(paths::store_path()) {
+        store.set("inventory", serde_json::to_value(&state.inventory).unwrap());
+        let _ = store.save();
+    }
+}
@@ -0,0 +1,374 @@
+use interprocess::local_socket::{prelude::*, ListenerOptions, Name, Stream};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands;
+use crate::launch;
+use crate::paths;
+use crate::state::{AppSettings, AppState, DeviceGroup, GroupDevice, SwitchSource};
+use crate::switcher;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const APP_ERROR: i64 = -32000;
+
+// Longest a client waits for an answer. Switches wait on pre-switch hooks, so
+// this leaves room for a slow one; longer means the app is stuck.
+const CALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        RpcError {
+            code: APP_ERROR,
+            message,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Response {
+    #[serde(default)]
+    result: Value,
+    error: Option<RpcError>,
+}
+
+/// Listens on the control socket and serves newline-delimited JSON-RPC requests
+/// against the running app, one thread per connection.
+pub fn start_server(app: AppHandle) -> Result<(), String> {
+    let name = socket_name().map_err(|e| format!("Invalid control socket name: {}", e))?;
+
+    // A socket file left behind by a crash would make binding fail
+    #[cfg(unix)]
+    {
+        secure_socket_dir()?;
+        let path = socket_path();
+        if path.exists() && Stream::connect(name.borrow()).is_err() {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+
+    let listener = ListenerOptions::new()
+        .name(name)
+        .create_sync()
+        .map_err(|e| format!("Failed to open control socket: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(socket_path(), std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict control socket: {}", e))?;
+    }
+
+    std::thread::spawn(move || {
+        for conn in listener.incoming().filter_map(Result::ok) {
+            let app = app.clone();
+            std::thread::spawn(move || serve(&app, conn));
+        }
+    });
+
+    Ok(())
+}
+
+fn serve(app: &AppHandle, conn: Stream) {
+    let mut conn = BufReader::new(conn);
+    let mut line = String::new();
+
+    while conn.read_line(&mut line).map_or(false, |n| n > 0) {
+        if !line.trim().is_empty() {
+            let response = respond(app, line.trim());
+            if writeln!(conn.get_mut(), "{}", response).is_err() {
+                return;
+            }
+        }
+        line.clear();
+    }
+}
+
+fn respond(app: &AppHandle, line: &str) -> Value {
+    let request: Request = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": RpcError { code: PARSE_ERROR, message: e.to_string() },
+            })
+        }
+    };
+
+    match dispatch(app, &request.method, &request.params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": request.id, "error": error }),
+    }
+}
+
+// Mirrors the Tauri commands, with the same parameter names in snake_case
+fn dispatch(app: &AppHandle, method: &str, params: &Value) -> Result<Value, RpcError> {
+    let state = app.state::<Mutex<AppState>>();
+
+    let result = match method {
+        "get_audio_devices" => to_value(commands::get_audio_devices(state)?),
+        "set_default_device" => {
+            switcher::set_default_device(
+                app,
+                &param::<String>(params, "device_id")?,
+                SwitchSource::ControlSocket,
+            )?;
+            Value::Null
+        }
+        "get_groups" => to_value(commands::get_groups(state)),
+        "create_group" => {
+            let group = commands::create_group(
+                app.clone(),
+                state,
+                param(params, "name")?,
+                param::<Vec<GroupDevice>>(params, "devices")?,
+                param(params, "shortcut")?,
+                param(params, "hooks")?,
+            )?;
+            let _ = app.emit("config-reloaded", ());
+            to_value(group)
+        }
+        "update_group" => {
+            commands::update_group(app.clone(), state, param::<DeviceGroup>(params, "group")?)?;
+            let _ = app.emit("config-reloaded", ());
+            Value::Null
+        }
+        "delete_group" => {
+            commands::delete_group(app.clone(), state, param(params, "group_id")?)?;
+            let _ = app.emit("config-reloaded", ());
+            Value::Null
+        }
+        // Unlike the Tauri commands, switching returns the new default device
+        "cycle_group" => to_value(switcher::cycle_group(
+            app,
+            &param::<String>(params, "group_id")?,
+            SwitchSource::ControlSocket,
+        )?),
+        "select_group_device" => to_value(switcher::select_group_device(
+            app,
+            &param::<String>(params, "group_id")?,
+            param(params, "device_index")?,
+            SwitchSource::ControlSocket,
+        )?),
+        "get_device_inventory" => to_value(commands::get_device_inventory(state)),
+        "prune_device_inventory" => to_value(commands::prune_device_inventory(
+            app.clone(),
+            param(params, "older_than_days")?,
+        )),
+        "forget_device" => {
+            commands::forget_device(app.clone(), param(params, "device_id")?)?;
+            Value::Null
+        }
+        "get_device_aliases" => to_value(commands::get_device_aliases(state)),
+        "set_device_alias" => {
+            commands::set_device_alias(
+                app.clone(),
+                state,
+                param(params, "device_id")?,
+                param(params, "alias")?,
+            )?;
+            let _ = app.emit("devices-changed", ());
+            Value::Null
+        }
+        "get_settings" => to_value(commands::get_settings(state)),
+        "update_settings" => {
+            commands::update_settings(
+                app.clone(),
+                state,
+                param::<AppSettings>(params, "settings")?,
+            )?;
+            let _ = app.emit("config-reloaded", ());
+            Value::Null
+        }
+        "get_config_dir" => to_value(commands::get_config_dir()),
+        "get_webhook_deliveries" => to_value(commands::get_webhook_deliveries()),
+        "send_test_webhook" => {
+            commands::send_test_webhook(app.clone())?;
+            Value::Null
+        }
+        // Used by a second launch to hand over its arguments, e.g. `--cycle <group>`
+        "handle_args" => {
+            launch::handle(app, &param::<Vec<String>>(params, "args")?, true);
+            Value::Null
+        }
+        _ => {
+            return Err(RpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Unknown method {}", method),
+            })
+        }
+    };
+
+    Ok(result)
+}
+
+// Missing parameters deserialize from null, so optional ones may be left out
+fn param<T: DeserializeOwned>(params: &Value, name: &str) -> Result<T, RpcError> {
+    let value = params.get(name).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value).map_err(|e| RpcError {
+        code: INVALID_PARAMS,
+        message: format!("Invalid parameter {}: {}", name, e),
+    })
+}
+
+fn to_value<T: Serialize>(value: T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Connection to the control socket of a running instance.
+pub struct Client {
+    // Away on the thread doing a call, and not given back if it times out
+    conn: Option<BufReader<Stream>>,
+    next_id: u64,
+}
+
+impl Client {
+    /// Connects to the instance using this config directory, if one is running.
+    pub fn connect() -> Option<Self> {
+        let name = socket_name().ok()?;
+        let conn = Stream::connect(name).ok()?;
+        Some(Client {
+            conn: Some(BufReader::new(conn)),
+            next_id: 1,
+        })
+    }
+
+    pub fn call<T: DeserializeOwned>(&mut self, method: &str, params: Value) -> Result<T, String> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id,
+            "method": method,
+            "params": params,
+        });
+        self.next_id += 1;
+
+        let not_responding = format!(
+            "SoundShift is running but didn't answer within {} seconds",
+            CALL_TIMEOUT.as_secs()
+        );
+        let mut conn = self.conn.take().ok_or_else(|| not_responding.clone())?;
+
+        // Local sockets can't time out on Windows, so wait on a thread instead
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = exchange(&mut conn, &request);
+            let _ = tx.send((conn, result));
+        });
+        let line = match rx.recv_timeout(CALL_TIMEOUT) {
+            Ok((conn, result)) => {
+                self.conn = Some(conn);
+                result?
+            }
+            Err(_) => return Err(not_responding),
+        };
+
+        let response: Response = serde_json::from_str(&line)
+            .map_err(|e| format!("Invalid response from SoundShift: {}", e))?;
+        if let Some(error) = response.error {
+            return Err(error.message);
+        }
+
+        serde_json::from_value(response.result).map_err(|e| e.to_string())
+    }
+}
+
+// Sends one request and reads the answer
+fn exchange(conn: &mut BufReader<Stream>, request: &Value) -> Result<String, String> {
+    writeln!(conn.get_mut(), "{}", request)
+        .map_err(|e| format!("Failed to reach SoundShift: {}", e))?;
+
+    let mut line = String::new();
+    conn.read_line(&mut line)
+        .map_err(|e| format!("Failed to read from SoundShift: {}", e))?;
+    Ok(line)
+}
+
+// Each config directory gets its own socket, so isolated instances don't collide
+fn socket_id() -> String {
+    // FNV-1a, which unlike the std hasher is stable between builds
+    let path = paths::config_dir().path.to_string_lossy();
+    let hash = path.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("soundshift-{:016x}", hash)
+}
+
+// Socket paths are limited to ~100 bytes, so keep them out of the config directory.
+// Without a runtime directory, use a folder of our own in the shared temp dir.
+#[cfg(unix)]
+fn socket_dir() -> std::path::PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(|| std::env::temp_dir().join(format!("soundshift-{}", uid())))
+}
+
+#[cfg(unix)]
+fn uid() -> u32 {
+    // getuid always succeeds
+    unsafe { libc::getuid() }
+}
+
+#[cfg(unix)]
+fn socket_path() -> std::path::PathBuf {
+    socket_dir().join(format!("{}.sock", socket_id()))
+}
+
+// Creates the socket's folder if needed, and refuses to use one that other
+// users could get into, since anyone reaching the socket can control the app
+#[cfg(unix)]
+fn secure_socket_dir() -> Result<(), String> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    let dir = socket_dir();
+    match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(format!("Failed to create {}: {}", dir.display(), e)),
+    }
+
+    // Not following links, so a link planted in the temp dir is caught too
+    let meta = std::fs::symlink_metadata(&dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    if !meta.is_dir() || meta.uid() != uid() {
+        return Err(format!("{} is not a folder you own", dir.display()));
+    }
+    if meta.mode() & 0o077 != 0 {
+        return Err(format!("{} is open to other users", dir.display()));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn socket_name() -> std::io::Result<Name<'static>> {
+    use interprocess::local_socket::{GenericFilePath, ToFsName};
+    socket_path().to_fs_name::<GenericFilePath>()
+}
+
+#[cfg(windows)]
+fn socket_name() -> std::io::Result<Name<'static>> {
+    use interprocess::local_socket::{GenericNamespaced, ToNsName};
+    // A named pipe, i.e. \\.\pipe\soundshift-<id>
+    socket_id().to_ns_name::<GenericNamespaced>()
+}
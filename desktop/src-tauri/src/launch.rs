@@ -0,0 +1,219 @@
+use serde_json::json;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::deeplink;
+use crate::ipc;
+use crate::paths;
+use crate::state::{AppState, SwitchSource};
+use crate::switcher;
+
+// Things a launch can ask the app to do
+enum Action {
+    Show,
+    Cycle(String),
+    Link(String),
+}
+
+// Taken by the instance running on a data folder, in that folder
+const LOCK_FILE_NAME: &str = "instance.lock";
+
+// How long a second launch waits for the running instance's socket, which
+// isn't open yet if both were started at once
+const CONNECT_ATTEMPTS: u32 = 20;
+const CONNECT_RETRY: Duration = Duration::from_millis(250);
+
+// Kept open for the life of the process, since closing it releases the lock
+static INSTANCE_LOCK: OnceLock<File> = OnceLock::new();
+
+/// Hands this launch's arguments to an instance already running on the same
+/// data folder. Returns true if one is running, in which case this process
+/// should exit instead of starting a second tray icon and set of shortcuts.
+pub fn forward_to_running_instance(args: &[String]) -> bool {
+    match lock_data_folder() {
+        Ok(true) => return false,
+        Ok(false) => {}
+        // Without the lock, going by whether something answers is the best we can do
+        Err(e) => {
+            eprintln!(
+                "Failed to lock {}: {}",
+                paths::config_dir().path.display(),
+                e
+            );
+            return match ipc::Client::connect() {
+                Some(client) => {
+                    forward(client, args);
+                    true
+                }
+                None => false,
+            };
+        }
+    }
+
+    let client = (0..CONNECT_ATTEMPTS).find_map(|attempt| {
+        if attempt > 0 {
+            std::thread::sleep(CONNECT_RETRY);
+        }
+        ipc::Client::connect()
+    });
+    match client {
+        Some(client) => forward(client, args),
+        None => eprintln!(
+            "SoundShift is already running for {}, but isn't answering",
+            paths::config_dir().path.display()
+        ),
+    }
+
+    true
+}
+
+// Takes the data folder's lock, which the OS holds until this process exits.
+// Returns false if another process has it.
+fn lock_data_folder() -> std::io::Result<bool> {
+    let dir = &paths::config_dir().path;
+    std::fs::create_dir_all(dir)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE_NAME))?;
+
+    match file.try_lock() {
+        Ok(()) => {
+            let _ = INSTANCE_LOCK.set(file);
+            Ok(true)
+        }
+        Err(TryLockError::WouldBlock) => Ok(false),
+        Err(TryLockError::Error(e)) => Err(e),
+    }
+}
+
+fn forward(mut client: ipc::Client, args: &[String]) {
+    if let Err(e) = client.call::<()>("handle_args", json!({ "args": args })) {
+        eprintln!(
+            "SoundShift is already running for {}, but didn't accept arguments: {}",
+            paths::config_dir().path.display(),
+            e
+        );
+    }
+}
+
+/// Carries out the actions in `args`. A repeated launch without any actions
+/// brings up the window, as users expect when starting an app that's running.
+pub fn handle(app: &AppHandle, args: &[String], show_if_idle: bool) {
+    let actions = parse(args);
+
+    if actions.is_empty() && show_if_idle {
+        show_main_window(app);
+    }
+
+    for action in actions {
+        match action {
+            Action::Show => show_main_window(app),
+            Action::Cycle(query) => {
+                let group_id = {
+                    let state = app.state::<Mutex<AppState>>();
+                    let state = state.lock().unwrap();
+                    switcher::find_group(&state.groups, &query).map(|g| g.id.clone())
+                };
+
+                match group_id {
+                    Some(group_id) => {
+                        if let Err(e) = switcher::cycle_group(app, &group_id, SwitchSource::Launch)
+                        {
+                            eprintln!("Failed to cycle group: {}", e);
+                        }
+                    }
+                    None => eprintln!("No group named {}", query),
+                }
+            }
+            Action::Link(url) => deeplink::handle(app, &url),
+        }
+    }
+}
+
+fn parse(args: &[String]) -> Vec<Action> {
+    let mut actions = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == "--show" {
+            actions.push(Action::Show);
+        } else if arg == "--cycle" {
+            match iter.next() {
+                Some(group) => actions.push(Action::Cycle(group.clone())),
+                None => eprintln!("--cycle needs a group name"),
+            }
+        } else if let Some(group) = arg.strip_prefix("--cycle=") {
+            actions.push(Action::Cycle(group.to_string()));
+        } else if is_link(arg) {
+            actions.push(Action::Link(arg.clone()));
+        }
+    }
+
+    actions
+}
+
+// Scheme names are case-insensitive
+fn is_link(arg: &str) -> bool {
+    arg.split_once(':').map_or(false, |(scheme, _)| {
+        scheme.eq_ignore_ascii_case(deeplink::SCHEME)
+    })
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_show_and_cycle() {
+        let actions = parse(&args(&["--show", "--cycle", "Gaming", "--cycle=Music"]));
+        assert!(matches!(
+            actions.as_slice(),
+            [Action::Show, Action::Cycle(a), Action::Cycle(b)] if a == "Gaming" && b == "Music"
+        ));
+    }
+
+    #[test]
+    fn cycle_without_group_is_dropped() {
+        assert!(parse(&args(&["--cycle"])).is_empty());
+    }
+
+    #[test]
+    fn picks_up_links_in_any_case() {
+        let links = [
+            "soundshift://cycle?group=Gaming",
+            "SoundShift://select?device=Headphones",
+        ];
+        let actions = parse(&args(&links));
+        assert!(matches!(
+            actions.as_slice(),
+            [Action::Link(a), Action::Link(b)] if a == links[0] && b == links[1]
+        ));
+    }
+
+    #[test]
+    fn ignores_other_arguments() {
+        let actions = parse(&args(&[
+            "--minimized",
+            "--config",
+            "C:\\data",
+            "https://example.com",
+        ]));
+        assert!(actions.is_empty());
+    }
+}
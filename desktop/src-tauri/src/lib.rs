@@ -1,99 +1,103 @@
 mod audio;
+mod cli;
 mod commands;
+mod config_file;
+#[cfg(target_os = "linux")]
+mod dbus;
+mod deeplink;
+mod hooks;
+mod http_api;
+mod identity;
+mod inventory;
+mod ipc;
+mod launch;
+mod mqtt;
+mod notify;
+mod osc;
+mod osd;
+mod paths;
+mod sound;
+mod speech;
 mod state;
+mod switcher;
+mod tray;
+mod webhooks;
 
+use std::collections::HashMap;
 use std::sync::Mutex;
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Emitter, Manager, RunEvent, WindowEvent,
-};
+use tauri::{Emitter, Manager, RunEvent, WindowEvent};
+use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
-use tauri_plugin_notification::NotificationExt;
 use tauri_plugin_store::StoreExt;
 
-use state::{AppSettings, AppState, DeviceGroup};
+use state::{AppSettings, AppState, DeviceGroup, InventoryEntry, SwitchSource};
 
 // Store the device listener handle to keep it alive
 static DEVICE_LISTENER: std::sync::OnceLock<audio::DeviceListenerHandle> = std::sync::OnceLock::new();
 
+/// Entry point for the `soundshift-cli` binary. Returns the process exit code.
+pub fn run_cli() -> i32 {
+    cli::run(std::env::args().skip(1).collect())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Only one instance per data folder; a second launch forwards its arguments
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if launch::forward_to_running_instance(&args) {
+        return;
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::default().build())
-        .plugin(tauri_plugin_autostart::init(
-            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            None,
-        ))
+        .plugin(
+            // Autostarted instances must use the same config directory as this one
+            tauri_plugin_autostart::Builder::new()
+                .args(paths::autostart_args())
+                .build(),
+        )
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
                 .with_handler(|app, shortcut, event| {
                     if event.state == ShortcutState::Pressed {
-                        // Get online devices first
-                        let online_devices = audio::get_audio_devices().unwrap_or_default();
-                        let online_ids: Vec<&str> = online_devices.iter().map(|d| d.id.as_str()).collect();
-
-                        let state = app.state::<Mutex<AppState>>();
-                        let mut state = state.lock().unwrap();
-
-                        // Find group with this shortcut and cycle
+                        // Find group with this shortcut
                         // Compare by parsing stored shortcut string to handle format differences
-                        if let Some(group) = state.groups.iter_mut().find(|g| {
-                            g.shortcut.as_ref().map_or(false, |stored| {
-                                stored
-                                    .parse::<Shortcut>()
-                                    .map_or(false, |parsed| parsed == *shortcut)
-                            })
-                        }) {
-                            if !group.devices.is_empty() {
-                                // Find online devices in this group
-                                let online_indices: Vec<usize> = group
-                                    .devices
-                                    .iter()
-                                    .enumerate()
-                                    .filter(|(_, d)| online_ids.contains(&d.id.as_str()))
-                                    .map(|(i, _)| i)
-                                    .collect();
-
-                                if online_indices.is_empty() {
-                                    return; // No online devices
-                                }
+                        let group_id = {
+                            let state = app.state::<Mutex<AppState>>();
+                            let state = state.lock().unwrap();
+                            state
+                                .groups
+                                .iter()
+                                .find(|g| {
+                                    g.shortcut.as_ref().map_or(false, |stored| {
+                                        stored
+                                            .parse::<Shortcut>()
+                                            .map_or(false, |parsed| parsed == *shortcut)
+                                    })
+                                })
+                                .map(|g| g.id.clone())
+                        };
 
-                                // Find current position in online devices and cycle to next
-                                let current_online_pos = online_indices
-                                    .iter()
-                                    .position(|&i| i == group.current_index)
-                                    .unwrap_or(0);
-                                let next_online_pos = (current_online_pos + 1) % online_indices.len();
-                                group.current_index = online_indices[next_online_pos];
-
-                                let device = group.devices[group.current_index].clone();
-
-                                // Set as default
-                                if let Err(e) = audio::set_default_device(&device.id) {
-                                    eprintln!("Failed to set device: {}", e);
-                                } else {
-                                    // Show notification
-                                    let _ = app.notification()
-                                        .builder()
-                                        .title("SoundShift")
-                                        .body(format!("Switched to {}", &device.name))
-                                        .show();
-
-                                    // Emit event to notify frontend
-                                    let _ = app.emit("device-switched", ());
+                        // Hooks can hold a switch up, so keep it off the main thread
+                        if let Some(group_id) = group_id {
+                            let app = app.clone();
+                            std::thread::spawn(move || {
+                                if let Err(e) = switcher::cycle_group(&app, &group_id, SwitchSource::Shortcut) {
+                                    eprintln!("Failed to cycle group: {}", e);
                                 }
-                            }
+                            });
                         }
                     }
                 })
                 .build(),
         )
         .manage(Mutex::new(AppState::default()))
-        .setup(|app| {
+        .setup(move |app| {
             // Load state from store
-            let store = app.store("config.json")?;
+            let store = app.store(paths::store_path())?;
 
             // Load groups
             if let Some(groups_value) = store.get("groups") {
@@ -125,50 +129,67 @@ pub fn run() {
                 }
             }
 
-            // Set up system tray
-            let show_i = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
-            let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
-
-            let tray_builder = TrayIconBuilder::new()
-                .menu(&menu)
-                .show_menu_on_left_click(false);
+            // Load device aliases
+            if let Some(aliases_value) = store.get("aliases") {
+                if let Ok(aliases) =
+                    serde_json::from_value::<HashMap<String, String>>(aliases_value.clone())
+                {
+                    let state = app.state::<Mutex<AppState>>();
+                    let mut state = state.lock().unwrap();
+                    state.aliases = aliases;
+                }
+            }
 
-            // Use default window icon if available, otherwise tray will use system default
-            let tray_builder = if let Some(icon) = app.default_window_icon() {
-                tray_builder.icon(icon.clone())
-            } else {
-                tray_builder
-            };
+            // Load device inventory
+            if let Some(inventory_value) = store.get("inventory") {
+                if let Ok(entries) =
+                    serde_json::from_value::<HashMap<String, InventoryEntry>>(inventory_value.clone())
+                {
+                    let state = app.state::<Mutex<AppState>>();
+                    let mut state = state.lock().unwrap();
+                    state.inventory = entries;
 
-            let _tray = tray_builder
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    _ => {}
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
+                    // Last seen was stamped on exit, so nothing is connected until scanned
+                    for entry in state.inventory.values_mut() {
+                        entry.connected = false;
                     }
-                })
-                .build(app)?;
+                }
+            }
+
+            // Devices may have come and gone while we weren't running
+            if let Ok(online) = audio::get_audio_devices() {
+                inventory::record(app.handle(), &online);
+                switcher::detect_external_switch(app.handle(), &online);
+            }
+
+            // Apply the TOML config file on top of the store, then watch it for edits
+            config_file::reload(app.handle());
+            if let Err(e) = config_file::start_watcher(app.handle().clone()) {
+                eprintln!("Failed to watch config file: {}", e);
+            }
+
+            // Start the integrations enabled in settings
+            http_api::start_event_relay(app.handle());
+            mqtt::start_event_relay(app.handle());
+            osc::start_event_relay(app.handle());
+            webhooks::start_event_relay(app.handle());
+            osd::start_event_relay(app.handle());
+            commands::settings_changed(app.handle());
+
+            // Let the CLI and other local tools drive this instance
+            if let Err(e) = ipc::start_server(app.handle().clone()) {
+                eprintln!("Failed to start control socket: {}", e);
+            }
+
+            // Let desktop widgets and scripts use the session bus instead
+            #[cfg(target_os = "linux")]
+            if let Err(e) = dbus::start(app.handle()) {
+                eprintln!("{}", e);
+            }
+
+            // Set up system tray, with a menu that follows devices and groups
+            tray::create(app.handle())?;
+            tray::start_event_relay(app.handle());
 
             // Check start minimized setting - hide window if user wants to start minimized
             let state = app.state::<Mutex<AppState>>();
@@ -183,9 +204,43 @@ pub fn run() {
                 }
             }
 
+            // Act on launch arguments such as `--cycle <group>` or `--show`,
+            // without holding up startup while a switch runs its hooks
+            let app_handle = app.handle().clone();
+            let launch_args = args.clone();
+            std::thread::spawn(move || launch::handle(&app_handle, &launch_args, false));
+
+            // Windows and Linux open links by launching the app with the URL as
+            // an argument, which reaches launch::handle; macOS hands them over here
+            #[cfg(target_os = "macos")]
+            {
+                let app_handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() {
+                        deeplink::handle(&app_handle, url.as_str());
+                    }
+                });
+            }
+
+            // Installers register the scheme, but portable copies need to do it themselves
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                eprintln!("Failed to register {}:// links: {}", deeplink::SCHEME, e);
+            }
+
             // Start device change listener
             let app_handle = app.handle().clone();
             if let Ok(listener) = audio::start_device_listener(move || {
+                if let Ok(online) = audio::get_audio_devices() {
+                    inventory::record(&app_handle, &online);
+
+                    // Follow devices that reappeared under a new ID
+                    switcher::relink_groups(&app_handle, &online);
+
+                    // Report default changes made outside SoundShift
+                    switcher::detect_external_switch(&app_handle, &online);
+                }
+
                 // Emit event to frontend when devices change
                 let _ = app_handle.emit("devices-changed", ());
             }) {
@@ -205,6 +260,23 @@ pub fn run() {
             commands::select_group_device,
             commands::get_settings,
             commands::update_settings,
+            commands::get_config_file_path,
+            commands::get_config_dir,
+            commands::get_device_inventory,
+            commands::prune_device_inventory,
+            commands::forget_device,
+            commands::get_device_aliases,
+            commands::set_device_alias,
+            commands::export_config_file,
+            commands::get_webhook_deliveries,
+            commands::get_hook_runs,
+            commands::send_test_webhook,
+            commands::get_osd,
+            commands::preview_osd,
+            commands::test_chime,
+            commands::get_output_channels,
+            commands::play_test_tone,
+            commands::test_announcement,
         ])
         .on_window_event(|window, event| {
             if let WindowEvent::CloseRequested { api, .. } = event {
@@ -219,6 +291,9 @@ pub fn run() {
                     // Prevent close and hide to tray instead
                     api.prevent_close();
                     let _ = window.hide();
+                } else {
+                    // A hidden overlay would keep the app running
+                    osd::close(app);
                 }
             }
         })
@@ -226,13 +301,19 @@ pub fn run() {
         .expect("error while building tauri application")
         .run(|app_handle, event| {
             if let RunEvent::ExitRequested { .. } = event {
+                // Stamp connected devices as last seen now
+                if let Ok(online) = audio::get_audio_devices() {
+                    inventory::record(app_handle, &online);
+                }
+
                 // Save state before exiting
                 let state = app_handle.state::<Mutex<AppState>>();
                 let state = state.lock().unwrap();
 
-                if let Ok(store) = app_handle.store("config.json") {
+                if let Ok(store) = app_handle.store(paths::store_path()) {
                     let _ = store.set("groups", serde_json::to_value(&state.groups).unwrap());
                     let _ = store.set("settings", serde_json::to_value(&state.settings).unwrap());
+                    let _ = store.set("aliases", serde_json::to_value(&state.aliases).unwrap());
                     let _ = store.save();
                 }
             }
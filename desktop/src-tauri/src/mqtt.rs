@@ -0,0 +1,200 @@
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+use uuid::Uuid;
+
+use crate::commands;
+use crate::state::{AppState, MqttSettings, SwitchSource};
+use crate::switcher;
+
+// Wait between attempts while the broker is unreachable
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+// Connected client and the settings it was created with
+static CLIENT: Mutex<Option<(MqttSettings, Client)>> = Mutex::new(None);
+
+// Bumped on every reconnect so the previous connection thread knows to stop
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// Payload of `<base>/select`; positions count from 1
+#[derive(Deserialize)]
+struct SelectCommand {
+    group: String,
+    position: usize,
+}
+
+/// Republishes the retained state whenever devices change or a switch happens.
+/// Called once at startup.
+pub fn start_event_relay(app: &AppHandle) {
+    for event in ["device-switched", "devices-changed"] {
+        let handle = app.clone();
+        app.listen_any(event, move |_| publish_state(&handle));
+    }
+}
+
+/// Connects, disconnects or reconnects to match the current settings.
+pub fn sync(app: &AppHandle) {
+    let settings = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.settings.mqtt.clone()
+    };
+
+    let mut client = CLIENT.lock().unwrap();
+    if let Some((current, _)) = client.as_ref() {
+        if settings.enabled && *current == settings {
+            return;
+        }
+    }
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    if let Some((_, previous)) = client.take() {
+        let _ = previous.try_disconnect();
+    }
+
+    if !settings.enabled || settings.host.trim().is_empty() {
+        return;
+    }
+
+    let base = base_topic(&settings);
+    let client_id = format!("soundshift-{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let mut options = MqttOptions::new(client_id, settings.host.trim(), settings.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    // Lets subscribers tell when SoundShift goes away
+    options.set_last_will(LastWill::new(
+        format!("{}/status", base),
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if !settings.username.is_empty() {
+        options.set_credentials(settings.username.as_str(), settings.password.as_str());
+    }
+
+    let (new_client, mut connection) = Client::new(options, 16);
+    *client = Some((settings, new_client));
+    drop(client);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        // Iterating drives the connection, including reconnecting after errors
+        for notification in connection.iter() {
+            if GENERATION.load(Ordering::SeqCst) != generation {
+                return;
+            }
+
+            match notification {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => on_connected(&app, &base),
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    let payload = String::from_utf8_lossy(&publish.payload).trim().to_string();
+                    handle_command(&app, &base, &publish.topic, &payload);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("MQTT connection error: {}", e);
+                    std::thread::sleep(RECONNECT_DELAY);
+                }
+            }
+        }
+    });
+}
+
+fn on_connected(app: &AppHandle, base: &str) {
+    if let Some((_, client)) = CLIENT.lock().unwrap().as_ref() {
+        for command in ["set", "cycle", "select"] {
+            let _ = client.try_subscribe(format!("{}/{}", base, command), QoS::AtLeastOnce);
+        }
+        let _ = client.try_publish(format!("{}/status", base), QoS::AtLeastOnce, true, "online");
+    }
+
+    publish_state(app);
+}
+
+// Publishes the device list and default device as retained messages, so new
+// subscribers get the current state straight away
+fn publish_state(app: &AppHandle) {
+    // Reading devices is slow and takes the app state lock, so don't hold
+    // the client lock meanwhile
+    let (settings, client) = match CLIENT.lock().unwrap().clone() {
+        Some(connected) => connected,
+        None => return,
+    };
+
+    let devices = match commands::get_audio_devices(app.state()) {
+        Ok(devices) => devices,
+        Err(e) => {
+            eprintln!("Failed to read devices for MQTT: {}", e);
+            return;
+        }
+    };
+
+    let base = base_topic(&settings);
+    let default = devices.iter().find(|d| d.is_default);
+    let messages = [
+        ("devices", serde_json::to_string(&devices)),
+        ("default", serde_json::to_string(&default)),
+    ];
+
+    for (topic, payload) in messages {
+        if let Ok(payload) = payload {
+            let _ = client.try_publish(
+                format!("{}/{}", base, topic),
+                QoS::AtLeastOnce,
+                true,
+                payload,
+            );
+        }
+    }
+}
+
+fn handle_command(app: &AppHandle, base: &str, topic: &str, payload: &str) {
+    let command = match topic.strip_prefix(base).and_then(|t| t.strip_prefix('/')) {
+        Some(command) => command,
+        None => return,
+    };
+
+    let result = match command {
+        // Device ID, name or alias
+        "set" => commands::get_audio_devices(app.state())
+            .and_then(|devices| {
+                switcher::lookup_device(&devices, payload).map(|device| device.id.clone())
+            })
+            .and_then(|id| switcher::set_default_device(app, &id, SwitchSource::Mqtt)),
+        // Group name or ID
+        "cycle" => find_group_id(app, payload)
+            .and_then(|id| switcher::cycle_group(app, &id, SwitchSource::Mqtt)),
+        "select" => serde_json::from_str::<SelectCommand>(payload)
+            .map_err(|e| format!("Invalid select payload: {}", e))
+            .and_then(|select| {
+                let index = switcher::position_index(select.position)?;
+                find_group_id(app, &select.group).and_then(|id| {
+                    switcher::select_group_device(app, &id, index, SwitchSource::Mqtt)
+                })
+            }),
+        _ => return,
+    };
+
+    if let Err(e) = result {
+        eprintln!("MQTT {} command failed: {}", command, e);
+    }
+}
+
+fn find_group_id(app: &AppHandle, query: &str) -> Result<String, String> {
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().unwrap();
+    switcher::find_group(&state.groups, query)
+        .map(|g| g.id.clone())
+        .ok_or_else(|| format!("No group named {}", query))
+}
+
+fn base_topic(settings: &MqttSettings) -> String {
+    let base = settings.base_topic.trim().trim_end_matches('/');
+    if base.is_empty() {
+        "soundshift".to_string()
+    } else {
+        base.to_string()
+    }
+}
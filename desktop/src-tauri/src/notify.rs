@@ -0,0 +1,304 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::audio;
+use crate::hooks::SwitchInfo;
+use crate::state::{AppState, AudioDevice, NotificationSettings, SwitchSource};
+use crate::switcher;
+
+// Button actions, as the notification hands them back
+const UNDO: &str = "undo";
+const NEXT: &str = "next";
+
+// What the buttons on a notification act on
+#[derive(Clone)]
+struct Followup {
+    group_id: String,
+    previous_id: Option<String>,
+}
+
+/// Shows the notification for a switch, unless it's turned off globally or for
+/// the group, `announced` says an announcement stood in for it, or a fullscreen
+/// app has the focus and that's set to suppress it.
+pub fn switched(app: &AppHandle, switch: &SwitchInfo, announced: bool) {
+    if announced {
+        return;
+    }
+
+    let settings = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        let group_hidden = switch
+            .group_id
+            .and_then(|id| state.groups.iter().find(|g| g.id == id))
+            .map_or(false, |g| g.hide_notifications);
+        if !state.settings.notifications.enabled || group_hidden {
+            return;
+        }
+        state.settings.notifications.clone()
+    };
+
+    if settings.suppress_fullscreen && fullscreen_app_focused() {
+        return;
+    }
+
+    let volume = match audio::get_volume(&switch.device.id) {
+        Ok(volume) if volume.muted => "muted".to_string(),
+        Ok(volume) => format!("{}%", (volume.level * 100.0).round()),
+        Err(_) => String::new(),
+    };
+
+    let template = if settings.template.trim().is_empty() {
+        NotificationSettings::default().template
+    } else {
+        settings.template
+    };
+    let body = render(
+        &template,
+        switch.device.display_name(),
+        switch.group_name.unwrap_or_default(),
+        &volume,
+    );
+
+    // Undo only makes sense if the switch changed something
+    let followup = match switch.group_id {
+        Some(group_id) if settings.actions => Some(Followup {
+            group_id: group_id.to_string(),
+            previous_id: switch
+                .previous
+                .filter(|previous| previous.id != switch.device.id)
+                .map(|previous| previous.id.clone()),
+        }),
+        _ => None,
+    };
+
+    match followup {
+        Some(followup) => show_with_buttons(app, body, followup),
+        None => {
+            let _ = app
+                .notification()
+                .builder()
+                .title("SoundShift")
+                .body(body)
+                .show();
+        }
+    }
+}
+
+fn buttons(followup: &Followup) -> Vec<(&'static str, &'static str)> {
+    let mut buttons = Vec::new();
+    if followup.previous_id.is_some() {
+        buttons.push((UNDO, "Undo"));
+    }
+    buttons.push((NEXT, "Next"));
+    buttons
+}
+
+fn run_action(app: &AppHandle, action: &str, followup: &Followup) {
+    let result = match (action, &followup.previous_id) {
+        (UNDO, Some(previous_id)) => undo(app, &followup.group_id, previous_id),
+        (NEXT, _) => switcher::cycle_group(app, &followup.group_id, SwitchSource::Notification),
+        // Clicking the notification itself; Linux says "default", Windows nothing
+        ("" | "default", _) => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            return;
+        }
+        // Closed without a click
+        _ => return,
+    };
+
+    if let Err(e) = result {
+        eprintln!("Notification action failed: {}", e);
+    }
+}
+
+// Goes back to the device from before the switch, through the group if it's a
+// member so the group's position goes back too
+fn undo(app: &AppHandle, group_id: &str, previous_id: &str) -> Result<AudioDevice, String> {
+    switcher::select_group_member(app, group_id, previous_id, SwitchSource::Notification)
+        .or_else(|_| switcher::set_default_device(app, previous_id, SwitchSource::Notification))
+}
+
+// Buttons are handled in-process rather than through a registered activator, so
+// they only work while the notification is on screen. Windows moves it to the
+// notification centre afterwards, where its buttons no longer reach SoundShift.
+#[cfg(windows)]
+fn show_with_buttons(app: &AppHandle, body: String, followup: Followup) {
+    use tauri_winrt_notification::Toast;
+
+    // Longest a toast stays on screen with Windows' accessibility settings
+    const MAX_ON_SCREEN: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+    // Development builds aren't registered with Windows, so borrow PowerShell's ID
+    let app_id = if tauri::is_dev() {
+        Toast::POWERSHELL_APP_ID.to_string()
+    } else {
+        app.config().identifier.clone()
+    };
+
+    let handle = app.clone();
+    std::thread::spawn(move || {
+        let mut toast = Toast::new(&app_id).title("SoundShift").text1(&body);
+        for (action, label) in buttons(&followup) {
+            toast = toast.add_button(label, action);
+        }
+
+        // The handlers live as long as the toast, so hold on to it until it's
+        // clicked or leaves the screen
+        let (done, closed) = std::sync::mpsc::channel();
+        let dismissed = done.clone();
+        let toast = toast
+            .on_activated(move |action| {
+                // Switch from a thread of our own rather than the one Windows calls back on
+                let handle = handle.clone();
+                let action = action.unwrap_or_default();
+                let followup = followup.clone();
+                std::thread::spawn(move || run_action(&handle, &action, &followup));
+                let _ = done.send(());
+                Ok(())
+            })
+            .on_dismissed(move |_| {
+                let _ = dismissed.send(());
+                Ok(())
+            });
+
+        match toast.show() {
+            Ok(()) => {
+                let _ = closed.recv_timeout(MAX_ON_SCREEN);
+            }
+            Err(e) => eprintln!("Failed to show notification: {}", e),
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn show_with_buttons(app: &AppHandle, body: String, followup: Followup) {
+    let mut notification = notify_rust::Notification::new();
+    notification.summary("SoundShift").body(&body).auto_icon();
+    for (action, label) in buttons(&followup) {
+        notification.action(action, label);
+    }
+
+    // Waiting for a button blocks until the notification closes
+    let handle = app.clone();
+    std::thread::spawn(move || match notification.show() {
+        Ok(shown) => shown.wait_for_action(|action| run_action(&handle, action, &followup)),
+        Err(e) => eprintln!("Failed to show notification: {}", e),
+    });
+}
+
+// Elsewhere notifications can't have buttons
+#[cfg(not(any(windows, target_os = "linux")))]
+fn show_with_buttons(app: &AppHandle, body: String, _followup: Followup) {
+    let _ = app
+        .notification()
+        .builder()
+        .title("SoundShift")
+        .body(body)
+        .show();
+}
+
+// Fills in {device}, {group} and {volume} in one pass, so names that contain
+// braces come out as they are. Anything else in braces is left alone.
+fn render(template: &str, device: &str, group: &str, volume: &str) -> String {
+    let mut body = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        body.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let value = ["device", "group", "volume"]
+            .into_iter()
+            .zip([device, group, volume])
+            .find(|(name, _)| {
+                rest[1..].starts_with(name) && rest[1 + name.len()..].starts_with('}')
+            });
+        match value {
+            Some((name, value)) => {
+                body.push_str(value);
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                body.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    body.push_str(rest);
+    body
+}
+
+// Fullscreen games, videos and presentations, going by what Windows itself
+// holds its own notifications back for
+#[cfg(windows)]
+fn fullscreen_app_focused() -> bool {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE,
+        QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    match unsafe { SHQueryUserNotificationState() } {
+        Ok(state) => {
+            state == QUNS_BUSY
+                || state == QUNS_RUNNING_D3D_FULL_SCREEN
+                || state == QUNS_PRESENTATION_MODE
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(windows))]
+fn fullscreen_app_focused() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_placeholders() {
+        assert_eq!(
+            render("{device} ({group}, {volume})", "Headphones", "Desk", "50%"),
+            "Headphones (Desk, 50%)"
+        );
+        assert_eq!(
+            render("{device} {device}", "Speakers", "", ""),
+            "Speakers Speakers"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_placeholders() {
+        assert_eq!(
+            render("{dev} {Device} {device }", "Headphones", "", ""),
+            "{dev} {Device} {device }"
+        );
+    }
+
+    #[test]
+    fn handles_nested_and_unclosed_braces() {
+        assert_eq!(
+            render("{{device}} {", "Headphones", "", ""),
+            "{Headphones} {"
+        );
+        assert_eq!(render("{device", "Headphones", "", ""), "{device");
+        assert_eq!(render("}{", "Headphones", "", ""), "}{");
+    }
+
+    #[test]
+    fn keeps_braces_in_names() {
+        // A device named like a placeholder isn't filled in again
+        assert_eq!(
+            render("{device} in {group}", "{group}", "Desk", ""),
+            "{group} in Desk"
+        );
+        assert_eq!(render("{device}", "Head{phones", "", ""), "Head{phones");
+    }
+}
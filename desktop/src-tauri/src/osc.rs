@@ -0,0 +1,347 @@
+use rosc::{OscMessage, OscPacket, OscType};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+
+use crate::commands;
+use crate::state::{AppState, AudioDevice, OscSettings, SwitchSource};
+use crate::switcher;
+
+const ADDRESS_PREFIX: &str = "/soundshift";
+
+// How often the listener checks whether it has been replaced
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static LISTENER: Mutex<Option<Listener>> = Mutex::new(None);
+
+// Threads of replaced listeners, which hold their port until they've stopped
+static STOPPING: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+// Held while a restart waits for the port, so restarts happen one at a time
+static RESTART: Mutex<()> = Mutex::new(());
+
+// Bumped on every restart so the previous listener thread knows to stop
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+struct Listener {
+    settings: OscSettings,
+    // Shares the listening port, for sending feedback
+    socket: UdpSocket,
+    thread: JoinHandle<()>,
+}
+
+/// Sends feedback to the configured address after every switch. Called once at startup.
+pub fn start_event_relay(app: &AppHandle) {
+    app.listen_any("device-switched", |event| {
+        let payload: serde_json::Value = match serde_json::from_str(event.payload()) {
+            Ok(payload) => payload,
+            Err(_) => return,
+        };
+
+        let name = payload["device"]["alias"]
+            .as_str()
+            .or(payload["device"]["name"].as_str())
+            .unwrap_or_default()
+            .to_string();
+        let id = payload["device"]["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let mut messages = vec![message(
+            format!("{}/default", ADDRESS_PREFIX),
+            vec![OscType::String(name.clone()), OscType::String(id)],
+        )];
+        if let Some(group) = payload["group_name"].as_str() {
+            messages.push(message(
+                format!("{}/group/{}/device", ADDRESS_PREFIX, address_part(group)),
+                vec![OscType::String(name)],
+            ));
+        }
+
+        send_feedback(messages);
+    });
+}
+
+/// Starts, stops or restarts the listener to match the current settings. A
+/// restart finishes in the background, so this returns straight away.
+pub fn sync(app: &AppHandle) {
+    let settings = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.settings.osc.clone()
+    };
+
+    let (generation, previous) = {
+        let mut listener = LISTENER.lock().unwrap();
+        if let Some(current) = listener.as_mut() {
+            // The socket is still bound as wanted, so other changes can be taken as is
+            if settings.enabled
+                && current.settings.port == settings.port
+                && current.settings.bind_address == settings.bind_address
+            {
+                current.settings = settings;
+                return;
+            }
+        }
+
+        let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        (generation, listener.take())
+    };
+
+    if let Some(previous) = previous {
+        drop(previous.socket);
+        STOPPING.lock().unwrap().push(previous.thread);
+    }
+
+    if !settings.enabled {
+        return;
+    }
+
+    // The old thread keeps the port until it notices it was replaced, within a
+    // poll interval, and binding it again before then would fail
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let _restart = RESTART.lock().unwrap();
+        let stopping = std::mem::take(&mut *STOPPING.lock().unwrap());
+        for thread in stopping {
+            let _ = thread.join();
+        }
+
+        if let Err(e) = start(&app, settings, generation) {
+            eprintln!("Failed to start OSC listener: {}", e);
+        }
+    });
+}
+
+// Binds and starts listening, unless settings changed again since `generation`
+fn start(app: &AppHandle, settings: OscSettings, generation: u64) -> Result<(), String> {
+    if GENERATION.load(Ordering::SeqCst) != generation {
+        return Ok(());
+    }
+
+    let bind_address = settings.bind_address.trim();
+    let socket = UdpSocket::bind((bind_address, settings.port))
+        .map_err(|e| format!("can't listen on {}:{}: {}", bind_address, settings.port, e))?;
+    let _ = socket.set_read_timeout(Some(POLL_INTERVAL));
+    let feedback_socket = socket.try_clone().map_err(|e| e.to_string())?;
+
+    // Holding the lock until the listener is stored keeps the new thread from
+    // seeing no listener, and so refusing everything, in the meantime
+    let mut listener = LISTENER.lock().unwrap();
+    if GENERATION.load(Ordering::SeqCst) != generation {
+        return Ok(());
+    }
+    let app = app.clone();
+    let thread = std::thread::spawn(move || {
+        let mut buf = [0u8; rosc::decoder::MTU];
+        while GENERATION.load(Ordering::SeqCst) == generation {
+            // Timeouts just give the loop a chance to notice it was replaced
+            let (size, from) = match socket.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(_) => continue,
+            };
+
+            if !allowed(from.ip()) {
+                eprintln!("Ignoring OSC message from {}: not an allowed host", from);
+                continue;
+            }
+
+            match rosc::decoder::decode_udp(&buf[..size]) {
+                Ok((_, packet)) => handle_packet(&app, packet),
+                Err(e) => eprintln!("Invalid OSC packet: {:?}", e),
+            }
+        }
+    });
+
+    *listener = Some(Listener {
+        settings,
+        socket: feedback_socket,
+        thread,
+    });
+    Ok(())
+}
+
+// Whether messages from `ip` are accepted, by the allowed hosts setting
+fn allowed(ip: IpAddr) -> bool {
+    let listener = LISTENER.lock().unwrap();
+    let hosts = match listener.as_ref() {
+        Some(listener) => &listener.settings.allowed_hosts,
+        None => return false,
+    };
+
+    hosts.is_empty()
+        || hosts
+            .iter()
+            .filter_map(|host| host.trim().parse::<IpAddr>().ok())
+            // IPv4 senders can show up as IPv6-mapped addresses
+            .any(|host| host.to_canonical() == ip.to_canonical())
+}
+
+fn handle_packet(app: &AppHandle, packet: OscPacket) {
+    match packet {
+        OscPacket::Message(message) => handle_message(app, message),
+        OscPacket::Bundle(bundle) => {
+            for packet in bundle.content {
+                handle_packet(app, packet);
+            }
+        }
+    }
+}
+
+fn handle_message(app: &AppHandle, message: OscMessage) {
+    // Buttons send 1 when pressed and 0 when released; only act on the press
+    if message.args.len() == 1 && number(&message.args[0]) == Some(0.0) {
+        return;
+    }
+
+    let path = match message.addr.strip_prefix(ADDRESS_PREFIX) {
+        Some(path) => path,
+        None => return,
+    };
+    let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let result = match parts.as_slice() {
+        ["group", group, "cycle"] => find_group_id(app, group)
+            .and_then(|id| switcher::cycle_group(app, &id, SwitchSource::Osc)),
+        // Positions count from 1, which also keeps them clear of button releases
+        ["group", group, "select"] => match message.args.first().and_then(number) {
+            Some(position) if position >= 1.0 && position.fract() == 0.0 => {
+                let index = position as usize - 1;
+                find_group_id(app, group).and_then(|id| {
+                    switcher::select_group_device(app, &id, index, SwitchSource::Osc)
+                })
+            }
+            _ => Err("select needs a device position, counting from 1".to_string()),
+        },
+        // Endpoint IDs contain {}, which OSC reserves for address patterns, so
+        // they go in an argument instead
+        ["device", "select"] => match message.args.first() {
+            Some(OscType::String(device)) => select_device(app, device),
+            _ => Err("select needs a device ID, name or alias".to_string()),
+        },
+        ["device", device, "select"] => select_device(app, device),
+        _ => return,
+    };
+
+    if let Err(e) = result {
+        eprintln!("OSC {} failed: {}", message.addr, e);
+    }
+}
+
+fn select_device(app: &AppHandle, query: &str) -> Result<AudioDevice, String> {
+    let devices = commands::get_audio_devices(app.state())?;
+    let id = match switcher::lookup_device(&devices, query) {
+        Ok(device) => device.id.clone(),
+        Err(e) => devices
+            .iter()
+            .find(|d| address_part(d.display_name()).eq_ignore_ascii_case(query))
+            .ok_or(e)?
+            .id
+            .clone(),
+    };
+    switcher::set_default_device(app, &id, SwitchSource::Osc)
+}
+
+// Names are also matched as written in addresses, so `Living_Room` finds
+// "Living Room"
+fn find_group_id(app: &AppHandle, query: &str) -> Result<String, String> {
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().unwrap();
+    switcher::find_group(&state.groups, query)
+        .or_else(|| {
+            state
+                .groups
+                .iter()
+                .find(|g| address_part(&g.name).eq_ignore_ascii_case(query))
+        })
+        .map(|g| g.id.clone())
+        .ok_or_else(|| format!("No group named {}", query))
+}
+
+// A group or device name as written in an address. Spaces, `/` and the
+// characters OSC reserves for patterns would change the address's meaning, so
+// anything but letters, digits, `_` and `-` becomes `_`.
+fn address_part(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn number(arg: &OscType) -> Option<f64> {
+    match *arg {
+        OscType::Int(value) => Some(value as f64),
+        OscType::Long(value) => Some(value as f64),
+        OscType::Float(value) => Some(value as f64),
+        OscType::Double(value) => Some(value),
+        OscType::Bool(value) => Some(if value { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn message(addr: String, args: Vec<OscType>) -> OscPacket {
+    OscPacket::Message(OscMessage { addr, args })
+}
+
+fn send_feedback(packets: Vec<OscPacket>) {
+    let listener = LISTENER.lock().unwrap();
+    let (settings, socket) = match listener.as_ref() {
+        Some(listener) => (&listener.settings, &listener.socket),
+        None => return,
+    };
+
+    if settings.feedback_address.trim().is_empty() {
+        return;
+    }
+
+    let target: SocketAddr = match settings
+        .feedback_address
+        .trim()
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+    {
+        Some(target) => target,
+        None => {
+            eprintln!("Invalid OSC feedback address {}", settings.feedback_address);
+            return;
+        }
+    };
+
+    for packet in packets {
+        if let Ok(bytes) = rosc::encoder::encode(&packet) {
+            let _ = socket.send_to(&bytes, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_part_keeps_safe_characters() {
+        assert_eq!(address_part("Desk-2_Main"), "Desk-2_Main");
+    }
+
+    #[test]
+    fn address_part_replaces_spaces_and_separators() {
+        assert_eq!(address_part("Living Room"), "Living_Room");
+        assert_eq!(address_part("Desk/Music"), "Desk_Music");
+    }
+
+    #[test]
+    fn address_part_replaces_pattern_characters() {
+        assert_eq!(address_part("a#b*c?d[e]f{g}h,i!j"), "a_b_c_d_e_f_g_h_i_j");
+        assert_eq!(address_part("Küche"), "K_che");
+    }
+}
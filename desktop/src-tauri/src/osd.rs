@@ -0,0 +1,220 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, Listener, Manager, PhysicalPosition, PhysicalSize, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder,
+};
+
+use crate::audio;
+use crate::commands;
+use crate::state::{AppState, OsdPosition, OsdSettings};
+
+const LABEL: &str = "osd";
+
+// Size of the overlay and its gap from the screen edge, in logical pixels
+const WIDTH: f64 = 360.0;
+const HEIGHT: f64 = 96.0;
+const MARGIN: f64 = 48.0;
+
+// What the overlay shows, kept for the page to fetch when it first loads
+static CURRENT: Mutex<Option<Osd>> = Mutex::new(None);
+
+// Counts showings, so only the latest one hides the overlay
+static SHOWN: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Osd {
+    pub device: String,
+    pub group: Option<String>,
+    // Percent, if the device reported its volume
+    pub volume: Option<u32>,
+    pub muted: bool,
+}
+
+/// Shows the overlay for every switch SoundShift makes. Called once at startup.
+pub fn start_event_relay(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any("device-switched", move |event| {
+        let switch: Value = match serde_json::from_str(event.payload()) {
+            Ok(switch) => switch,
+            Err(_) => return,
+        };
+
+        // Changes made in Windows settings already show there
+        if switch["source"] == "external" {
+            return;
+        }
+
+        let device = &switch["device"];
+        let name = device["alias"]
+            .as_str()
+            .or(device["name"].as_str())
+            .unwrap_or_default();
+        let osd = overlay_for(
+            device["id"].as_str().unwrap_or_default(),
+            name,
+            switch["group_name"].as_str(),
+        );
+
+        // Window calls wait on the main thread, which may be the one emitting
+        let handle = handle.clone();
+        std::thread::spawn(move || show(&handle, osd, false));
+    });
+}
+
+/// Closes the overlay window when the overlay is turned off.
+pub fn sync(app: &AppHandle) {
+    let enabled = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.settings.osd.enabled
+    };
+
+    if !enabled {
+        close(app);
+    }
+}
+
+/// Destroys the overlay window, which would otherwise keep the app running
+/// after the main window closes.
+pub fn close(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.destroy();
+    }
+}
+
+/// Shows the overlay for the current default device, to try out the settings.
+pub fn preview(app: &AppHandle) -> Result<(), String> {
+    let devices = commands::get_audio_devices(app.state())?;
+    let device = devices
+        .iter()
+        .find(|d| d.is_default)
+        .ok_or("No default output device")?;
+    let osd = overlay_for(&device.id, device.display_name(), None);
+
+    let handle = app.clone();
+    std::thread::spawn(move || show(&handle, osd, true));
+    Ok(())
+}
+
+/// What the overlay is showing, or last showed.
+pub fn current() -> Option<Osd> {
+    CURRENT.lock().unwrap().clone()
+}
+
+fn overlay_for(device_id: &str, name: &str, group: Option<&str>) -> Osd {
+    let volume = audio::get_volume(device_id).ok();
+    Osd {
+        device: name.to_string(),
+        group: group.map(str::to_string),
+        volume: volume.map(|v| (v.level * 100.0).round() as u32),
+        muted: volume.map_or(false, |v| v.muted),
+    }
+}
+
+// Shows the overlay, then hides it once the duration is up unless something
+// newer is showing by then. Blocks for the duration.
+fn show(app: &AppHandle, osd: Osd, preview: bool) {
+    let settings = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.settings.osd.clone()
+    };
+    if !settings.enabled && !preview {
+        return;
+    }
+
+    *CURRENT.lock().unwrap() = Some(osd.clone());
+
+    let window = match window(app) {
+        Ok(window) => window,
+        Err(e) => {
+            eprintln!("Failed to create overlay: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = place(app, &window, &settings) {
+        eprintln!("Failed to position overlay: {}", e);
+    }
+    let _ = app.emit_to(LABEL, "osd-show", &osd);
+    let _ = window.show();
+
+    let shown = SHOWN.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::sleep(Duration::from_millis(
+        settings.duration_ms.clamp(500, 10_000),
+    ));
+    if SHOWN.load(Ordering::SeqCst) == shown {
+        let _ = window.hide();
+    }
+}
+
+// The overlay window, created hidden the first time it's needed
+fn window(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        return Ok(window);
+    }
+
+    let builder = WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("osd".into()))
+        .title("SoundShift")
+        .inner_size(WIDTH, HEIGHT)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .shadow(false)
+        .focusable(false)
+        .focused(false)
+        .visible(false);
+
+    // macOS only allows transparent windows through private APIs
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.transparent(true);
+
+    let window = builder.build()?;
+    // Clicks go through to whatever is underneath
+    window.set_ignore_cursor_events(true)?;
+    Ok(window)
+}
+
+// Puts the overlay in the chosen spot on the screen the cursor is on
+fn place(app: &AppHandle, window: &WebviewWindow, settings: &OsdSettings) -> tauri::Result<()> {
+    let cursor = app.cursor_position()?;
+    let monitor = match app.monitor_from_point(cursor.x, cursor.y)? {
+        Some(monitor) => monitor,
+        None => match app.primary_monitor()? {
+            Some(monitor) => monitor,
+            None => return Ok(()),
+        },
+    };
+
+    // Work area leaves out the taskbar
+    let area = monitor.work_area();
+    let scale = monitor.scale_factor();
+    let width = (WIDTH * scale) as i32;
+    let height = (HEIGHT * scale) as i32;
+    let margin = (MARGIN * scale) as i32;
+    let (left, top) = (area.position.x, area.position.y);
+    let (right, bottom) = (left + area.size.width as i32, top + area.size.height as i32);
+
+    let x = match settings.position {
+        OsdPosition::TopLeft | OsdPosition::BottomLeft => left + margin,
+        OsdPosition::Top | OsdPosition::Center | OsdPosition::Bottom => {
+            left + (right - left - width) / 2
+        }
+        OsdPosition::TopRight | OsdPosition::BottomRight => right - width - margin,
+    };
+    let y = match settings.position {
+        OsdPosition::TopLeft | OsdPosition::Top | OsdPosition::TopRight => top + margin,
+        OsdPosition::Center => top + (bottom - top - height) / 2,
+        OsdPosition::BottomLeft | OsdPosition::Bottom | OsdPosition::BottomRight => {
+            bottom - height - margin
+        }
+    };
+
+    window.set_size(PhysicalSize::new(width as u32, height as u32))?;
+    window.set_position(PhysicalPosition::new(x, y))
+}
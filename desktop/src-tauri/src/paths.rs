@@ -0,0 +1,132 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// Must match `identifier` in tauri.conf.json so the default directory is the
+// same one Tauri resolves as the app data directory
+const APP_IDENTIFIER: &str = "com.soundshift.app";
+
+const CONFIG_ENV_VAR: &str = "SOUNDSHIFT_CONFIG";
+
+// Placing a file with this name next to the executable enables portable mode
+const PORTABLE_MARKER: &str = "portable";
+
+// Portable data lives in this folder next to the executable
+const PORTABLE_DIR_NAME: &str = "data";
+
+pub const STORE_FILE_NAME: &str = "config.json";
+
+static CONFIG_DIR: OnceLock<ConfigDir> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Flag,
+    Env,
+    Portable,
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigDir {
+    pub path: PathBuf,
+    pub source: ConfigSource,
+}
+
+/// Directory holding the store and config file. Resolved once per process, in order
+/// of precedence: `--config <dir>`, `SOUNDSHIFT_CONFIG`, portable mode, app data dir.
+pub fn config_dir() -> &'static ConfigDir {
+    CONFIG_DIR.get_or_init(|| {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        resolve(&args)
+    })
+}
+
+pub fn store_path() -> PathBuf {
+    config_dir().path.join(STORE_FILE_NAME)
+}
+
+/// Arguments that make an autostarted instance use the same config directory.
+/// The default location and portable mode from the marker file don't need any.
+pub fn autostart_args() -> Vec<String> {
+    let dir = config_dir();
+    match dir.source {
+        ConfigSource::Flag | ConfigSource::Env => vec![
+            "--config".to_string(),
+            dir.path.to_string_lossy().into_owned(),
+        ],
+        ConfigSource::Portable if portable_flag(std::env::args().skip(1)) => {
+            vec!["--portable".to_string()]
+        }
+        ConfigSource::Portable | ConfigSource::Default => Vec::new(),
+    }
+}
+
+fn resolve(args: &[String]) -> ConfigDir {
+    if let Some(path) = flag_value(args, "--config") {
+        return ConfigDir {
+            path: absolute(Path::new(&path)),
+            source: ConfigSource::Flag,
+        };
+    }
+
+    if let Some(path) = std::env::var_os(CONFIG_ENV_VAR).filter(|p| !p.is_empty()) {
+        return ConfigDir {
+            path: absolute(Path::new(&path)),
+            source: ConfigSource::Env,
+        };
+    }
+
+    if let Some(exe_dir) = exe_dir() {
+        if portable_flag(args.iter()) || exe_dir.join(PORTABLE_MARKER).exists() {
+            return ConfigDir {
+                path: exe_dir.join(PORTABLE_DIR_NAME),
+                source: ConfigSource::Portable,
+            };
+        }
+    }
+
+    let path = dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .unwrap_or_else(|| PathBuf::from(APP_IDENTIFIER));
+
+    ConfigDir {
+        path,
+        source: ConfigSource::Default,
+    }
+}
+
+fn portable_flag<S: AsRef<str>>(mut args: impl Iterator<Item = S>) -> bool {
+    args.any(|a| a.as_ref() == "--portable")
+}
+
+// Supports both `--flag value` and `--flag=value`
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    let prefix = format!("{}=", flag);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            return iter.next().cloned();
+        }
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+}
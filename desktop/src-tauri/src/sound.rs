@@ -0,0 +1,262 @@
+use rodio::cpal::traits::HostTrait;
+use rodio::source::{SineWave, Zero};
+use rodio::{Decoder, DeviceTrait, OutputStream, Sink, Source};
+use std::f32::consts::TAU;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::state::{AppState, AudioDevice};
+
+// Notes of the built-in chime, as frequency in Hz and length in milliseconds
+const CHIME: [(f32, u64); 2] = [(880.0, 90), (1318.5, 160)];
+
+// Test tones: pitch, a lower one the subwoofer can play, and timings
+const TONE_HZ: f32 = 440.0;
+const SUBWOOFER_HZ: f32 = 60.0;
+const TONE_LENGTH: Duration = Duration::from_millis(1500);
+const SWEEP_LENGTH: Duration = Duration::from_millis(700);
+const SWEEP_GAP: Duration = Duration::from_millis(250);
+
+/// Plays the confirmation chime on `device` if chimes are turned on, using the
+/// device's own sound and volume where it has them. Returns straight away.
+pub fn chime(app: &AppHandle, device: &AudioDevice) {
+    let (enabled, sound, volume) = chime_for(app, device);
+    if !enabled || volume == 0 {
+        return;
+    }
+
+    let device = device.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = play_chime(&device, sound.trim(), volume) {
+            eprintln!("Failed to play chime on {}: {}", device.display_name(), e);
+        }
+    });
+}
+
+/// Plays the chime `device` would get, even with chimes turned off, and waits
+/// for it to finish.
+pub fn test_chime(app: &AppHandle, device: &AudioDevice) -> Result<(), String> {
+    let (_, sound, volume) = chime_for(app, device);
+    play_chime(device, sound.trim(), volume)
+}
+
+// Whether chimes are on, and the sound and volume for `device`
+fn chime_for(app: &AppHandle, device: &AudioDevice) -> (bool, String, u32) {
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().unwrap();
+    let settings = &state.settings.chime;
+    let own = settings.devices.get(&device.id);
+
+    (
+        settings.enabled,
+        own.and_then(|c| c.sound.clone())
+            .unwrap_or_else(|| settings.sound.clone()),
+        own.and_then(|c| c.volume).unwrap_or(settings.volume),
+    )
+}
+
+// Plays `sound`, or the built-in chime when it's empty, and waits for it to end
+fn play_chime(device: &AudioDevice, sound: &str, volume: u32) -> Result<(), String> {
+    let output = output_device(device)?;
+    let (_stream, handle) = OutputStream::try_from_device(&output).map_err(|e| e.to_string())?;
+    let sink = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+    sink.set_volume(volume.min(100) as f32 / 100.0);
+
+    if sound.is_empty() {
+        for (frequency, millis) in CHIME {
+            sink.append(
+                SineWave::new(frequency)
+                    .take_duration(Duration::from_millis(millis))
+                    .amplify(0.3),
+            );
+        }
+    } else {
+        let file = File::open(sound).map_err(|e| format!("Can't open {}: {}", sound, e))?;
+        let source = Decoder::new(BufReader::new(file))
+            .map_err(|e| format!("Can't play {}: {}", sound, e))?;
+        sink.append(source);
+    }
+
+    // Dropping the stream cuts the sound off
+    sink.sleep_until_end();
+    Ok(())
+}
+
+/// Finds `device` among playback devices, to play to it directly rather than
+/// to whatever the default is. cpal doesn't expose endpoint IDs, so both sides
+/// go by the Windows friendly name, and a name shared by several devices is an
+/// error rather than a guess.
+pub fn output_device(device: &AudioDevice) -> Result<rodio::Device, String> {
+    let mut matches = rodio::cpal::default_host()
+        .output_devices()
+        .map_err(|e| e.to_string())?
+        .filter(|output| output.name().map_or(false, |name| name == device.name));
+
+    match (matches.next(), matches.next()) {
+        (Some(output), None) => Ok(output),
+        (Some(_), Some(_)) => Err(format!(
+            "More than one playback device is called {}; rename one in Windows sound settings to play to it",
+            device.name
+        )),
+        (None, _) => Err(format!("{} is not available for playback", device.display_name())),
+    }
+}
+
+/// Names for `device`'s output channels, in the order the driver numbers them.
+pub fn output_channels(device: &AudioDevice) -> Result<Vec<String>, String> {
+    let config = output_device(device)?
+        .default_output_config()
+        .map_err(|e| e.to_string())?;
+    Ok(channel_names(config.channels()))
+}
+
+/// Plays a tone on one channel of `device`, or on each channel in turn when
+/// `channel` is None, without touching the default device. Blocks until done.
+pub fn play_test_tone(device: &AudioDevice, channel: Option<u16>) -> Result<(), String> {
+    let output = output_device(device)?;
+    let config = output.default_output_config().map_err(|e| e.to_string())?;
+    let channels = config.channels();
+    let sample_rate = config.sample_rate().0;
+
+    let names = channel_names(channels);
+    let (targets, length) = match channel {
+        Some(channel) if channel < channels => (vec![channel], TONE_LENGTH),
+        Some(channel) => {
+            return Err(format!(
+                "{} has no channel {} (it has {})",
+                device.display_name(),
+                channel,
+                channels
+            ))
+        }
+        None => ((0..channels).collect(), SWEEP_LENGTH),
+    };
+
+    let (_stream, handle) = OutputStream::try_from_device(&output).map_err(|e| e.to_string())?;
+    let sink = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+    for target in targets {
+        let frequency = if names[target as usize] == "Subwoofer" {
+            SUBWOOFER_HZ
+        } else {
+            TONE_HZ
+        };
+        sink.append(ChannelTone::new(
+            channels,
+            target,
+            sample_rate,
+            frequency,
+            length,
+        ));
+        sink.append(Zero::<f32>::new(channels, sample_rate).take_duration(SWEEP_GAP));
+    }
+
+    sink.sleep_until_end();
+    Ok(())
+}
+
+// Usual speaker layouts for each channel count, as Windows orders them
+fn channel_names(channels: u16) -> Vec<String> {
+    let layout: &[&str] = match channels {
+        1 => &["Mono"],
+        2 => &["Left", "Right"],
+        4 => &["Front left", "Front right", "Rear left", "Rear right"],
+        6 => &[
+            "Front left",
+            "Front right",
+            "Center",
+            "Subwoofer",
+            "Rear left",
+            "Rear right",
+        ],
+        8 => &[
+            "Front left",
+            "Front right",
+            "Center",
+            "Subwoofer",
+            "Rear left",
+            "Rear right",
+            "Side left",
+            "Side right",
+        ],
+        _ => &[],
+    };
+
+    (0..channels as usize)
+        .map(|i| match layout.get(i) {
+            Some(name) => name.to_string(),
+            None => format!("Channel {}", i + 1),
+        })
+        .collect()
+}
+
+// A sine tone on one channel of an interleaved stream, silent on the others
+struct ChannelTone {
+    channels: u16,
+    target: u16,
+    sample_rate: u32,
+    frequency: f32,
+    frames: usize,
+    // Samples produced so far, across all channels
+    position: usize,
+}
+
+impl ChannelTone {
+    fn new(channels: u16, target: u16, sample_rate: u32, frequency: f32, length: Duration) -> Self {
+        Self {
+            channels,
+            target,
+            sample_rate,
+            frequency,
+            frames: (length.as_secs_f32() * sample_rate as f32) as usize,
+            position: 0,
+        }
+    }
+}
+
+impl Iterator for ChannelTone {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let frame = self.position / self.channels as usize;
+        if frame >= self.frames {
+            return None;
+        }
+        let channel = (self.position % self.channels as usize) as u16;
+        self.position += 1;
+
+        if channel != self.target {
+            return Some(0.0);
+        }
+
+        // 10ms fades at each end, so the tone doesn't click on and off
+        let fade_frames = self.sample_rate as f32 / 100.0;
+        let edge = frame.min(self.frames - 1 - frame) as f32;
+        let fade = (edge / fade_frames).min(1.0);
+
+        let t = frame as f32 / self.sample_rate as f32;
+        Some((t * self.frequency * TAU).sin() * 0.3 * fade)
+    }
+}
+
+impl Source for ChannelTone {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.frames as f32 / self.sample_rate as f32,
+        ))
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::state::{AppState, AudioDevice};
+
+/// Says the name or alias of the device switched to, if announcements are
+/// turned on. It's spoken on the new default device, since that's where
+/// speech goes. Returns whether it was said and stands in for the switch
+/// notification, so a failed announcement never leaves a switch unreported.
+pub fn announce(app: &AppHandle, device: &AudioDevice) -> bool {
+    let settings = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.settings.speech.clone()
+    };
+
+    if !settings.enabled {
+        return false;
+    }
+
+    match say(device.display_name()) {
+        Ok(()) => settings.replace_notification,
+        Err(e) => {
+            eprintln!("Failed to announce {}: {}", device.display_name(), e);
+            false
+        }
+    }
+}
+
+/// Says `text` on the default device, cutting off anything still being said.
+/// Returns once the engine has taken it, not when it's done talking.
+#[cfg(windows)]
+pub fn say(text: &str) -> Result<(), String> {
+    use std::sync::mpsc::{self, Sender};
+    use std::time::Duration;
+
+    // Starting the engine the first time can take a moment
+    const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+    // Text to say, and where to report whether it's being said
+    type Request = (String, Sender<Result<(), String>>);
+
+    // The speech engine lives on a thread of its own, since it stops talking
+    // when dropped and isn't Send
+    static SPEAKER: Mutex<Option<Sender<Request>>> = Mutex::new(None);
+
+    let mut speaker = SPEAKER.lock().unwrap();
+    let sender = speaker.get_or_insert_with(|| {
+        let (tx, rx) = mpsc::channel::<Request>();
+        std::thread::spawn(move || {
+            let mut tts = match tts::Tts::default() {
+                Ok(tts) => tts,
+                Err(e) => {
+                    eprintln!("Failed to start text-to-speech: {}", e);
+                    return;
+                }
+            };
+            for (text, reply) in rx {
+                // A newer switch cuts off the last announcement
+                let result = tts.speak(text, true).map(|_| ()).map_err(|e| e.to_string());
+                let _ = reply.send(result);
+            }
+        });
+        tx
+    });
+
+    // The thread ends if the engine didn't start, dropping the request
+    // unanswered; try again next time
+    let (reply, result) = mpsc::channel();
+    let sent = sender.send((text.to_string(), reply)).is_ok();
+    match result.recv_timeout(REPLY_TIMEOUT) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) if sent => {
+            Err("Text-to-speech didn't respond".to_string())
+        }
+        Err(_) => {
+            *speaker = None;
+            Err("Text-to-speech is not available".to_string())
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn say(_text: &str) -> Result<(), String> {
+    Err("Text-to-speech is only supported on Windows".to_string())
+}
@@ -1,33 +1,168 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioDevice {
     pub id: String,
     pub name: String,
     pub is_default: bool,
+    // Identifies the physical device; survives the endpoint ID changing
+    #[serde(default)]
+    pub container_id: Option<String>,
+    // User-defined display name, filled in from the alias map
+    #[serde(default)]
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub kind: DeviceKind,
+}
+
+// What sort of output a device is, from the form factor its driver reports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceKind {
+    Speakers,
+    // Headphones and headsets
+    Headphones,
+    // HDMI and DisplayPort monitors and TVs
+    Display,
+    #[default]
+    Other,
+}
+
+impl AudioDevice {
+    /// The alias if the user set one, otherwise the OS friendly name.
+    pub fn display_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
 }
 
 // Device reference stored in a group (persists even when device is offline)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupDevice {
+    // Optional for pattern entries in the TOML config file
+    #[serde(default)]
     pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+    // Pattern on the device name, used to re-link the entry if its ID changes.
+    // A plain string is read as a wildcard.
+    #[serde(
+        default,
+        deserialize_with = "relink_pattern",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub match_pattern: Option<DeviceMatcher>,
+    // Makes this a dynamic entry standing for every live device whose name
+    // matches; `id` and `name` are then only labels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<DeviceMatcher>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "pattern", rename_all = "snake_case")]
+pub enum DeviceMatcher {
+    Wildcard(String),
+    Regex(String),
+}
+
+// Earlier versions stored the re-link pattern as a bare wildcard string
+fn relink_pattern<'de, D>(deserializer: D) -> Result<Option<DeviceMatcher>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Pattern {
+        Wildcard(String),
+        Matcher(DeviceMatcher),
+    }
+
+    Ok(
+        Option::<Pattern>::deserialize(deserializer)?.map(|pattern| match pattern {
+            Pattern::Wildcard(pattern) => DeviceMatcher::Wildcard(pattern),
+            Pattern::Matcher(matcher) => matcher,
+        }),
+    )
+}
+
+// How a group entry was matched to a live device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    Id,
+    Container,
+    Name,
+    Pattern,
+}
+
+// Record of a group entry being moved to a device's new ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceRelink {
+    pub old_id: String,
+    pub new_id: String,
     pub name: String,
+    pub matched_by: MatchKind,
+    pub at: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceGroup {
+    // Optional in the TOML config file, where groups may be written by hand
+    #[serde(default)]
     pub id: String,
     pub name: String,
+    #[serde(default)]
     pub devices: Vec<GroupDevice>,
     pub shortcut: Option<String>,
+    #[serde(default)]
     pub current_index: usize,
+    // Live device picked for the current entry, which may be a pattern
+    #[serde(default)]
+    pub current_device_id: Option<String>,
+    #[serde(default)]
+    pub relinks: Vec<DeviceRelink>,
+    // Run around switches made through this group, after the global hooks
+    #[serde(default, skip_serializing_if = "GroupHooks::is_empty")]
+    pub hooks: GroupHooks,
+    // No notifications for switches made through this group
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hide_notifications: bool,
+}
+
+// Shell commands run before and after a switch; empty runs nothing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupHooks {
+    pub pre_switch: String,
+    pub post_switch: String,
+}
+
+impl GroupHooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_switch.trim().is_empty() && self.post_switch.trim().is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
     pub run_on_startup: bool,
     pub start_minimized: bool,
     pub close_to_tray: bool,
+    pub http_api: HttpApiSettings,
+    pub mqtt: MqttSettings,
+    pub osc: OscSettings,
+    pub deep_links: DeepLinkSettings,
+    pub hooks: HookSettings,
+    pub webhooks: WebhookSettings,
+    pub tray: TraySettings,
+    pub notifications: NotificationSettings,
+    pub osd: OsdSettings,
+    pub chime: ChimeSettings,
+    pub speech: SpeechSettings,
 }
 
 impl Default for AppSettings {
@@ -36,12 +171,364 @@ impl Default for AppSettings {
             run_on_startup: false,
             start_minimized: false,
             close_to_tray: true,
+            http_api: HttpApiSettings::default(),
+            mqtt: MqttSettings::default(),
+            osc: OscSettings::default(),
+            deep_links: DeepLinkSettings::default(),
+            hooks: HookSettings::default(),
+            webhooks: WebhookSettings::default(),
+            tray: TraySettings::default(),
+            notifications: NotificationSettings::default(),
+            osd: OsdSettings::default(),
+            chime: ChimeSettings::default(),
+            speech: SpeechSettings::default(),
+        }
+    }
+}
+
+// Opt-in REST API on localhost for stream decks and dashboards
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    // Required on every request; generated when the API is first enabled
+    pub token: String,
+    // Web pages allowed to call the API, e.g. "http://localhost:3000". Requests
+    // from any other page are refused.
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7879,
+            token: String::new(),
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+// Connection to an MQTT broker for home automation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    // Prefix for every topic SoundShift publishes or listens on
+    pub base_topic: String,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            username: String::new(),
+            password: String::new(),
+            base_topic: "soundshift".to_string(),
+        }
+    }
+}
+
+// UDP listener for OSC controllers such as TouchOSC
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OscSettings {
+    pub enabled: bool,
+    // Interface to listen on; 0.0.0.0 takes messages from the whole network
+    pub bind_address: String,
+    pub port: u16,
+    // IP addresses messages are accepted from; empty accepts any
+    pub allowed_hosts: Vec<String>,
+    // host:port that switch feedback is sent to; empty sends none
+    pub feedback_address: String,
+}
+
+impl Default for OscSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 9000,
+            allowed_hosts: Vec::new(),
+            feedback_address: String::new(),
+        }
+    }
+}
+
+// Handling of soundshift:// links
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeepLinkSettings {
+    pub enabled: bool,
+    // Ask before following a link, since any web page or document can contain one
+    pub confirm: bool,
+}
+
+impl Default for DeepLinkSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            confirm: true,
+        }
+    }
+}
+
+// Shell commands run around every switch, before any group's own hooks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookSettings {
+    pub pre_switch: String,
+    pub post_switch: String,
+    // Hooks still running after this long are killed
+    pub timeout_secs: u64,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self {
+            pre_switch: String::new(),
+            post_switch: String::new(),
+            timeout_secs: 10,
         }
     }
 }
 
+// URLs that every switch is posted to as JSON
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub urls: Vec<String>,
+}
+
+// What the tray icon does when clicked or scrolled over
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraySettings {
+    pub left_click: TrayAction,
+    pub middle_click: TrayAction,
+    // Scroll over the icon to change the default device's volume
+    pub scroll_volume: bool,
+    // Percentage points per notch of the mouse wheel
+    pub volume_step: u32,
+}
+
+impl Default for TraySettings {
+    fn default() -> Self {
+        Self {
+            left_click: TrayAction::ShowWindow,
+            middle_click: TrayAction::Nothing,
+            scroll_volume: true,
+            volume_step: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TrayAction {
+    ShowWindow,
+    CycleGroup { group_id: String },
+    // Mute or unmute the default device
+    ToggleMute,
+    Nothing,
+}
+
+// The notification shown after a group switch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    // Body text, with {device}, {group} and {volume} filled in
+    pub template: String,
+    // Hold notifications back while a fullscreen game or video has the focus
+    pub suppress_fullscreen: bool,
+    // Undo and Next buttons that act on the group that switched
+    pub actions: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            template: "Switched to {device}".to_string(),
+            suppress_fullscreen: false,
+            actions: true,
+        }
+    }
+}
+
+// On-screen overlay shown briefly after each switch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OsdSettings {
+    pub enabled: bool,
+    pub position: OsdPosition,
+    // How long the overlay stays up
+    pub duration_ms: u64,
+}
+
+impl Default for OsdSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            position: OsdPosition::Bottom,
+            duration_ms: 1500,
+        }
+    }
+}
+
+// Where on the screen the overlay goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OsdPosition {
+    TopLeft,
+    Top,
+    TopRight,
+    Center,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+// Sound played on the new device once it's the default
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChimeSettings {
+    pub enabled: bool,
+    // Path to a WAV, MP3, FLAC or Ogg file; empty plays the built-in chime
+    pub sound: String,
+    // Percent
+    pub volume: u32,
+    // Overrides keyed by device ID
+    pub devices: HashMap<String, DeviceChime>,
+}
+
+impl Default for ChimeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sound: String::new(),
+            volume: 50,
+            devices: HashMap::new(),
+        }
+    }
+}
+
+// A device's own chime; unset fields use the global ones
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceChime {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sound: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<u32>,
+}
+
+// Spoken announcement of the new device after a switch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeechSettings {
+    pub enabled: bool,
+    // Skip the notification while announcements are on
+    pub replace_notification: bool,
+}
+
+impl Default for SpeechSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            replace_notification: true,
+        }
+    }
+}
+
+// Payload of the "device-switched" event
+#[derive(Debug, Clone, Serialize)]
+pub struct SwitchEvent {
+    pub device: AudioDevice,
+    // Default device before the switch, if there was one
+    pub previous: Option<AudioDevice>,
+    // Set when the switch came from cycling or selecting within a group
+    pub group_id: Option<String>,
+    pub group_name: Option<String>,
+    pub source: SwitchSource,
+}
+
+// What asked for a switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwitchSource {
+    // The SoundShift window
+    App,
+    Shortcut,
+    Tray,
+    // The control socket, which the CLI goes through
+    ControlSocket,
+    Launch,
+    Link,
+    HttpApi,
+    Mqtt,
+    Osc,
+    Dbus,
+    // Undo and Next on a switch notification
+    Notification,
+    // Windows settings or another app; seen when the device list refreshes
+    External,
+}
+
+// Every device SoundShift has ever seen, keyed by device ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryEntry {
+    pub id: String,
+    pub last_known_name: String,
+    #[serde(default)]
+    pub container_id: Option<String>,
+    pub first_seen: u64,
+    pub last_seen: u64,
+    // Whether the device was present at the last scan
+    #[serde(default)]
+    pub connected: bool,
+}
+
 #[derive(Debug, Default)]
 pub struct AppState {
     pub groups: Vec<DeviceGroup>,
     pub settings: AppSettings,
+    pub inventory: HashMap<String, InventoryEntry>,
+    // User-defined display names keyed by device ID
+    pub aliases: HashMap<String, String>,
+}
+
+impl AppState {
+    /// Fills in user-defined aliases on devices fresh from the OS.
+    pub fn apply_aliases(&self, devices: &mut [AudioDevice]) {
+        for device in devices {
+            device.alias = self.aliases.get(&device.id).cloned();
+        }
+    }
+
+    /// Aliases are keyed by ID, so carry them over to re-linked devices.
+    pub fn follow_relinks(&mut self, relinks: &[DeviceRelink]) {
+        for relink in relinks {
+            if let Some(alias) = self.aliases.remove(&relink.old_id) {
+                self.aliases.insert(relink.new_id.clone(), alias);
+            }
+        }
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
@@ -0,0 +1,352 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::audio;
+use crate::commands;
+use crate::hooks::{self, Stage, SwitchInfo};
+use crate::identity;
+use crate::notify;
+use crate::sound;
+use crate::speech;
+use crate::state::{AppState, AudioDevice, DeviceGroup, DeviceRelink, SwitchEvent, SwitchSource};
+
+// Default device as of the last switch or device scan, to tell when something
+// else changes it
+static LAST_DEFAULT: Mutex<Option<AudioDevice>> = Mutex::new(None);
+
+/// Cycles a group to its next online device and makes it the default.
+pub fn cycle_group(
+    app: &AppHandle,
+    group_id: &str,
+    source: SwitchSource,
+) -> Result<AudioDevice, String> {
+    switch(app, group_id, source, next_member)
+}
+
+/// Makes a specific entry in a group the default. Pattern entries resolve to
+/// their first matching device.
+pub fn select_group_device(
+    app: &AppHandle,
+    group_id: &str,
+    device_index: usize,
+    source: SwitchSource,
+) -> Result<AudioDevice, String> {
+    switch(app, group_id, source, |group, online| {
+        member_at(group, online, device_index)
+    })
+}
+
+/// Makes a specific live device the default as a member of a group, for
+/// entries such as patterns that stand for several devices.
+pub fn select_group_member(
+    app: &AppHandle,
+    group_id: &str,
+    device_id: &str,
+    source: SwitchSource,
+) -> Result<AudioDevice, String> {
+    switch(app, group_id, source, |group, online| {
+        identity::resolve_members(group, online)
+            .into_iter()
+            .find(|(_, d)| d.id == device_id)
+            .map(|(i, device)| (i, device.clone()))
+            .ok_or_else(|| "Device is not in the group or is offline".to_string())
+    })
+}
+
+/// Makes a device the default directly, outside of any group.
+pub fn set_default_device(
+    app: &AppHandle,
+    device_id: &str,
+    source: SwitchSource,
+) -> Result<AudioDevice, String> {
+    let mut online_devices = audio::get_audio_devices()?;
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.apply_aliases(&mut online_devices);
+    }
+
+    let previous = online_devices.iter().find(|d| d.is_default).cloned();
+    let mut device = online_devices
+        .into_iter()
+        .find(|d| d.id == device_id)
+        .ok_or("Device is offline")?;
+
+    let info = SwitchInfo {
+        previous: previous.as_ref(),
+        device: &device,
+        group_id: None,
+        group_name: None,
+    };
+    hooks::run(app, Stage::PreSwitch, &info);
+    make_default(&device)?;
+    sound::chime(app, &device);
+    speech::announce(app, &device);
+    hooks::run(app, Stage::PostSwitch, &info);
+
+    device.is_default = true;
+
+    let _ = app.emit(
+        "device-switched",
+        SwitchEvent {
+            device: device.clone(),
+            previous,
+            group_id: None,
+            group_name: None,
+            source,
+        },
+    );
+
+    Ok(device)
+}
+
+/// Compares the default device with the last one seen, and reports a switch if
+/// something outside SoundShift changed it. Call with every fresh device list.
+pub fn detect_external_switch(app: &AppHandle, online: &[AudioDevice]) {
+    let mut current = match online.iter().find(|d| d.is_default) {
+        Some(device) => device.clone(),
+        None => return,
+    };
+
+    let previous = {
+        let mut last = LAST_DEFAULT.lock().unwrap();
+        if last.as_ref().map(|d| &d.id) == Some(&current.id) {
+            return;
+        }
+        last.replace(current.clone())
+    };
+
+    // The first scan only records where things stand
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return,
+    };
+
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        state.apply_aliases(std::slice::from_mut(&mut current));
+    }
+
+    let _ = app.emit(
+        "device-switched",
+        SwitchEvent {
+            device: current,
+            previous: Some(previous),
+            group_id: None,
+            group_name: None,
+            source: SwitchSource::External,
+        },
+    );
+}
+
+/// Re-links every group against the live device list, saving if anything moved.
+pub fn relink_groups(app: &AppHandle, online: &[AudioDevice]) {
+    let state = app.state::<Mutex<AppState>>();
+    let relinks = {
+        let mut state = state.lock().unwrap();
+        let relinks: Vec<DeviceRelink> = state
+            .groups
+            .iter_mut()
+            .flat_map(|group| identity::relink_group(group, online))
+            .collect();
+        state.follow_relinks(&relinks);
+        relinks
+    };
+
+    if !relinks.is_empty() {
+        let _ = commands::save_groups(app, &state);
+        commands::save_aliases(app, &state);
+    }
+}
+
+/// Finds a group by ID, or failing that by case-insensitive name.
+pub fn find_group<'a>(groups: &'a [DeviceGroup], query: &str) -> Option<&'a DeviceGroup> {
+    groups
+        .iter()
+        .find(|g| g.id == query)
+        .or_else(|| groups.iter().find(|g| g.name.eq_ignore_ascii_case(query)))
+}
+
+/// Finds a device by ID, then by exact name or alias, then by a unique part of one.
+pub fn lookup_device<'a>(
+    devices: &'a [AudioDevice],
+    query: &str,
+) -> Result<&'a AudioDevice, String> {
+    if let Some(device) = devices.iter().find(|d| d.id == query) {
+        return Ok(device);
+    }
+
+    let query_lower = query.to_lowercase();
+    let names = |d: &AudioDevice| {
+        [
+            Some(d.name.to_lowercase()),
+            d.alias.as_ref().map(|a| a.to_lowercase()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+    };
+
+    if let Some(device) = devices
+        .iter()
+        .find(|d| names(d).iter().any(|n| *n == query_lower))
+    {
+        return Ok(device);
+    }
+
+    let partial: Vec<&AudioDevice> = devices
+        .iter()
+        .filter(|d| names(d).iter().any(|n| n.contains(&query_lower)))
+        .collect();
+
+    match partial.as_slice() {
+        [device] => Ok(device),
+        [] => Err(format!("No device matches {}", query)),
+        _ => Err(format!(
+            "{} matches several devices: {}",
+            query,
+            partial
+                .iter()
+                .map(|d| d.display_name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Picks the online member that follows the group's current one.
+pub fn next_member(
+    group: &DeviceGroup,
+    online: &[AudioDevice],
+) -> Result<(usize, AudioDevice), String> {
+    if group.devices.is_empty() {
+        return Err("Group has no devices".to_string());
+    }
+
+    // Find online devices in this group
+    let members = identity::resolve_members(group, online);
+
+    if members.is_empty() {
+        return Err("No online devices in group".to_string());
+    }
+
+    // Find current position in online members and cycle to next
+    let current_pos = group
+        .current_device_id
+        .as_ref()
+        .and_then(|id| members.iter().position(|(_, d)| &d.id == id))
+        .or_else(|| members.iter().position(|(i, _)| *i == group.current_index))
+        .unwrap_or(0);
+    let (index, device) = members[(current_pos + 1) % members.len()];
+
+    Ok((index, device.clone()))
+}
+
+/// Turns a device's position in a group as people give it, counting from 1 as
+/// the CLI, links and integrations all do, into the index of its entry.
+pub fn position_index(position: usize) -> Result<usize, String> {
+    position
+        .checked_sub(1)
+        .ok_or_else(|| "Device positions start at 1".to_string())
+}
+
+/// Resolves the entry at `device_index` to a live device.
+pub fn member_at(
+    group: &DeviceGroup,
+    online: &[AudioDevice],
+    device_index: usize,
+) -> Result<(usize, AudioDevice), String> {
+    if device_index >= group.devices.len() {
+        return Err("Invalid device index".to_string());
+    }
+
+    identity::resolve_members(group, online)
+        .into_iter()
+        .find(|(i, _)| *i == device_index)
+        .map(|(i, device)| (i, device.clone()))
+        .ok_or_else(|| "Device is offline".to_string())
+}
+
+// Records the device before switching, so the device scan the switch itself
+// triggers isn't taken for an outside change
+fn make_default(device: &AudioDevice) -> Result<(), String> {
+    let before = LAST_DEFAULT.lock().unwrap().replace(device.clone());
+    audio::set_default_device(&device.id).inspect_err(|_| {
+        *LAST_DEFAULT.lock().unwrap() = before;
+    })
+}
+
+fn switch<F>(
+    app: &AppHandle,
+    group_id: &str,
+    source: SwitchSource,
+    pick: F,
+) -> Result<AudioDevice, String>
+where
+    F: FnOnce(&DeviceGroup, &[AudioDevice]) -> Result<(usize, AudioDevice), String>,
+{
+    // Get online devices first
+    let mut online_devices = audio::get_audio_devices().unwrap_or_default();
+
+    let state = app.state::<Mutex<AppState>>();
+    let mut state_guard = state.lock().unwrap();
+
+    let group_index = state_guard
+        .groups
+        .iter()
+        .position(|g| g.id == group_id)
+        .ok_or("Group not found")?;
+
+    // Devices that came back under a new ID count as online again
+    let relinks = identity::relink_group(&mut state_guard.groups[group_index], &online_devices);
+    state_guard.follow_relinks(&relinks);
+    state_guard.apply_aliases(&mut online_devices);
+    let previous = online_devices.iter().find(|d| d.is_default).cloned();
+
+    let group = &mut state_guard.groups[group_index];
+    let (index, mut device) = pick(group, &online_devices)?;
+    group.current_index = index;
+    group.current_device_id = Some(device.id.clone());
+    let group_name = group.name.clone();
+
+    drop(state_guard);
+
+    if !relinks.is_empty() {
+        commands::save_aliases(app, &state);
+    }
+
+    let info = SwitchInfo {
+        previous: previous.as_ref(),
+        device: &device,
+        group_id: Some(group_id),
+        group_name: Some(&group_name),
+    };
+    hooks::run(app, Stage::PreSwitch, &info);
+
+    // Set as default device
+    make_default(&device)?;
+    sound::chime(app, &device);
+    let announced = speech::announce(app, &device);
+    hooks::run(app, Stage::PostSwitch, &info);
+    notify::switched(app, &info, announced);
+
+    device.is_default = true;
+
+    // Emit event to notify frontend and integrations
+    let _ = app.emit(
+        "device-switched",
+        SwitchEvent {
+            device: device.clone(),
+            previous,
+            group_id: Some(group_id.to_string()),
+            group_name: Some(group_name),
+            source,
+        },
+    );
+
+    // Save to store
+    commands::save_groups(app, &state)?;
+
+    Ok(device)
+}
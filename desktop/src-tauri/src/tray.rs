@@ -0,0 +1,498 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{include_image, AppHandle, Listener, Manager, Wry};
+
+use crate::audio::{self, Volume};
+use crate::identity;
+use crate::state::{
+    AppState, AudioDevice, DeviceGroup, DeviceKind, SwitchSource, TrayAction, TraySettings,
+};
+use crate::switcher;
+
+const TRAY_ID: &str = "main";
+
+// Menu item IDs carry what to switch to, so a click on a menu that has since
+// been rebuilt still does what its label said
+const DEVICE_PREFIX: &str = "device:";
+const MEMBER_PREFIX: &str = "member:";
+
+/// Creates the tray icon and its menu. Called once at startup.
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let tray_builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("SoundShift")
+        .show_menu_on_left_click(false);
+
+    // Use default window icon if available, otherwise tray will use system default
+    let tray_builder = if let Some(icon) = app.default_window_icon() {
+        tray_builder.icon(icon.clone())
+    } else {
+        tray_builder
+    };
+
+    tray_builder
+        .on_menu_event(|app, event| handle_menu_event(app, event.id.as_ref()))
+        .on_tray_icon_event(|tray, event| handle_icon_event(tray.app_handle(), event))
+        .build(app)?;
+
+    #[cfg(windows)]
+    wheel::start(app);
+
+    refresh(app);
+    Ok(())
+}
+
+/// Updates the tray whenever devices, groups or the default device change.
+pub fn start_event_relay(app: &AppHandle) {
+    for event in ["device-switched", "devices-changed", "config-reloaded"] {
+        let handle = app.clone();
+        app.listen_any(event, move |_| refresh(&handle));
+    }
+}
+
+/// Rebuilds the tray menu, and sets the icon and tooltip for the default device.
+pub fn refresh(app: &AppHandle) {
+    // Nothing to do until the tray exists
+    let tray = match app.tray_by_id(TRAY_ID) {
+        Some(tray) => tray,
+        None => return,
+    };
+
+    let (online, groups, aliases) = snapshot(app);
+
+    let menu = build_menu(app, &online, &groups, &aliases);
+    if let Err(e) = menu.and_then(|menu| tray.set_menu(Some(menu))) {
+        eprintln!("Failed to update tray menu: {}", e);
+    }
+
+    set_status(app, &tray, &online, &groups);
+}
+
+// Updates just the icon and tooltip, after volume and mute changes that leave
+// the menu as it was
+fn refresh_status(app: &AppHandle) {
+    let tray = match app.tray_by_id(TRAY_ID) {
+        Some(tray) => tray,
+        None => return,
+    };
+
+    let (online, groups, _) = snapshot(app);
+    set_status(app, &tray, &online, &groups);
+}
+
+// Online devices with aliases applied, groups and aliases
+fn snapshot(app: &AppHandle) -> (Vec<AudioDevice>, Vec<DeviceGroup>, HashMap<String, String>) {
+    let mut online = audio::get_audio_devices().unwrap_or_default();
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().unwrap();
+    state.apply_aliases(&mut online);
+    (online, state.groups.clone(), state.aliases.clone())
+}
+
+fn set_status(app: &AppHandle, tray: &TrayIcon, online: &[AudioDevice], groups: &[DeviceGroup]) {
+    let default = online.iter().find(|d| d.is_default);
+    let volume = default.and_then(|device| audio::get_volume(&device.id).ok());
+
+    let icon = match icon(default, volume.map_or(false, |v| v.muted)) {
+        Some(icon) => Some(icon),
+        None => app.default_window_icon().cloned(),
+    };
+    let _ = tray.set_icon(icon);
+    let _ = tray.set_tooltip(Some(tooltip(default, volume, groups)));
+}
+
+fn build_menu(
+    app: &AppHandle,
+    online: &[AudioDevice],
+    groups: &[DeviceGroup],
+    aliases: &HashMap<String, String>,
+) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+
+    // A submenu per group, listing its entries in cycle order
+    for group in groups {
+        let submenu = Submenu::new(app, label(&group.name), true)?;
+        let members = identity::resolve_members(group, online);
+        let current = current_member(group, &members);
+
+        for (index, entry) in group.devices.iter().enumerate() {
+            let matched: Vec<&AudioDevice> = members
+                .iter()
+                .filter(|(i, _)| *i == index)
+                .map(|(_, device)| *device)
+                .collect();
+
+            // Offline entries stay listed, so the group reads the same as in the window
+            if matched.is_empty() {
+                let name = aliases.get(&entry.id).unwrap_or(&entry.name);
+                submenu.append(&MenuItem::new(app, label(name), false, None::<&str>)?)?;
+            }
+
+            for device in matched {
+                let id = serde_json::to_string(&[&group.id, &device.id]).unwrap();
+                submenu.append(&CheckMenuItem::with_id(
+                    app,
+                    format!("{}{}", MEMBER_PREFIX, id),
+                    label(device.display_name()),
+                    true,
+                    current == Some(device.id.as_str()),
+                    None::<&str>,
+                )?)?;
+            }
+        }
+
+        if group.devices.is_empty() {
+            submenu.append(&MenuItem::new(app, "No devices", false, None::<&str>)?)?;
+        }
+
+        menu.append(&submenu)?;
+    }
+
+    if !groups.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    // Every output device, for switching outside of any group
+    for device in online {
+        menu.append(&CheckMenuItem::with_id(
+            app,
+            format!("{}{}", DEVICE_PREFIX, device.id),
+            label(device.display_name()),
+            true,
+            device.is_default,
+            None::<&str>,
+        )?)?;
+    }
+
+    if online.is_empty() {
+        menu.append(&MenuItem::new(
+            app,
+            "No output devices",
+            false,
+            None::<&str>,
+        )?)?;
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "show", "Show", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+
+    Ok(menu)
+}
+
+fn handle_icon_event(app: &AppHandle, event: TrayIconEvent) {
+    match event {
+        TrayIconEvent::Click {
+            button,
+            button_state: MouseButtonState::Up,
+            ..
+        } => {
+            let settings = tray_settings(app);
+            let action = match button {
+                MouseButton::Left => settings.left_click,
+                MouseButton::Middle => settings.middle_click,
+                // Right-click opens the menu
+                MouseButton::Right => return,
+            };
+            run_action(app, action);
+        }
+        #[cfg(windows)]
+        TrayIconEvent::Enter { rect, .. } => {
+            if tray_settings(app).scroll_volume {
+                wheel::hover(Some(rect));
+            }
+        }
+        #[cfg(windows)]
+        TrayIconEvent::Leave { .. } => wheel::hover(None),
+        _ => {}
+    }
+}
+
+fn run_action(app: &AppHandle, action: TrayAction) {
+    match action {
+        TrayAction::ShowWindow => show_window(app),
+        TrayAction::Nothing => {}
+        // Switching and muting talk to the audio system and can run hooks, so
+        // they happen on a worker rather than the thread handling tray events
+        action => {
+            let app = app.clone();
+            std::thread::spawn(move || {
+                let result = match action {
+                    TrayAction::CycleGroup { group_id } => {
+                        switcher::cycle_group(&app, &group_id, SwitchSource::Tray).map(|_| ())
+                    }
+                    TrayAction::ToggleMute => toggle_mute(&app),
+                    _ => Ok(()),
+                };
+
+                if let Err(e) = result {
+                    eprintln!("Tray click failed: {}", e);
+                }
+            });
+        }
+    }
+}
+
+fn toggle_mute(app: &AppHandle) -> Result<(), String> {
+    let device = default_device()?;
+    let volume = audio::get_volume(&device.id)?;
+    audio::set_mute(&device.id, !volume.muted)?;
+    refresh_status(app);
+    Ok(())
+}
+
+// Moves the default device's volume by `notches` volume steps, up for positive
+fn step_volume(app: &AppHandle, notches: i32) -> Result<(), String> {
+    let step = tray_settings(app).volume_step.clamp(1, 100) as f32 / 100.0;
+    let device = default_device()?;
+    let volume = audio::get_volume(&device.id)?;
+    audio::set_volume(&device.id, volume.level + notches as f32 * step)?;
+    refresh_status(app);
+    Ok(())
+}
+
+fn default_device() -> Result<AudioDevice, String> {
+    audio::get_audio_devices()?
+        .into_iter()
+        .find(|d| d.is_default)
+        .ok_or_else(|| "No default output device".to_string())
+}
+
+fn tray_settings(app: &AppHandle) -> TraySettings {
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().unwrap();
+    state.settings.tray.clone()
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "show" => show_window(app),
+        "quit" => app.exit(0),
+        // Switches can wait on hooks, so they run off the main thread
+        _ => {
+            let app = app.clone();
+            let id = id.to_string();
+            std::thread::spawn(move || switch_from_menu(&app, &id));
+        }
+    }
+}
+
+fn switch_from_menu(app: &AppHandle, id: &str) {
+    let result = if let Some(device_id) = id.strip_prefix(DEVICE_PREFIX) {
+        switcher::set_default_device(app, device_id, SwitchSource::Tray).map(|_| ())
+    } else if let Some(member) = id.strip_prefix(MEMBER_PREFIX) {
+        match serde_json::from_str::<[String; 2]>(member) {
+            Ok([group_id, device_id]) => {
+                switcher::select_group_member(app, &group_id, &device_id, SwitchSource::Tray)
+                    .map(|_| ())
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    } else {
+        return;
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to switch from tray: {}", e);
+        // Clicking a check item toggles it, so put the marks back as they were
+        refresh(app);
+    }
+}
+
+// The device a group is on: its last switch, or failing that the first live
+// device for its current entry
+fn current_member<'a>(
+    group: &'a DeviceGroup,
+    members: &[(usize, &'a AudioDevice)],
+) -> Option<&'a str> {
+    match group.current_device_id {
+        Some(ref id) => Some(id.as_str()),
+        None => members
+            .iter()
+            .find(|(i, _)| *i == group.current_index)
+            .map(|(_, device)| device.id.as_str()),
+    }
+}
+
+// Icon for the default device's kind, or None to use the app icon
+fn icon(device: Option<&AudioDevice>, muted: bool) -> Option<Image<'static>> {
+    let device = device?;
+    if muted {
+        return Some(include_image!("icons/tray/muted.png"));
+    }
+
+    match device.kind {
+        DeviceKind::Headphones => Some(include_image!("icons/tray/headphones.png")),
+        DeviceKind::Speakers => Some(include_image!("icons/tray/speakers.png")),
+        DeviceKind::Display => Some(include_image!("icons/tray/display.png")),
+        DeviceKind::Other => None,
+    }
+}
+
+// "SoundShift", then the default device, the group it's the current device of
+// and its volume
+fn tooltip(device: Option<&AudioDevice>, volume: Option<Volume>, groups: &[DeviceGroup]) -> String {
+    let device = match device {
+        Some(device) => device,
+        None => return "SoundShift".to_string(),
+    };
+
+    let mut tooltip = format!("SoundShift\n{}", device.display_name());
+    if let Some(group) = groups
+        .iter()
+        .find(|g| g.current_device_id.as_ref() == Some(&device.id))
+    {
+        tooltip.push_str(&format!(" ({})", group.name));
+    }
+    match volume {
+        Some(volume) if volume.muted => tooltip.push_str(", muted"),
+        Some(volume) => tooltip.push_str(&format!(", {}%", (volume.level * 100.0).round())),
+        None => {}
+    }
+    tooltip
+}
+
+// Menus treat `&` as a mnemonic marker, so device names like "Speakers & Headphones"
+// need it doubled
+fn label(text: &str) -> String {
+    text.replace('&', "&&")
+}
+
+fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+// Windows doesn't send mouse wheel messages to tray icons, so while the cursor
+// is over the icon a low-level mouse hook picks them up instead
+#[cfg(windows)]
+mod wheel {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::mpsc::{self, Sender};
+    use std::sync::Mutex;
+    use tauri::{AppHandle, Rect};
+    use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetMessageW, PeekMessageW, PostThreadMessageW, SetWindowsHookExW,
+        UnhookWindowsHookEx, HHOOK, MSG, MSLLHOOKSTRUCT, PM_NOREMOVE, WHEEL_DELTA, WH_MOUSE_LL,
+        WM_APP, WM_MOUSEWHEEL,
+    };
+
+    // Messages to the hook thread
+    const WM_HOOK: u32 = WM_APP;
+    const WM_UNHOOK: u32 = WM_APP + 1;
+
+    // Screen area of the icon while the cursor is over it, as left, top, right, bottom
+    static ICON_AREA: Mutex<Option<(i32, i32, i32, i32)>> = Mutex::new(None);
+
+    // Wheel movement over the icon, for the thread that changes the volume
+    static WHEEL: Mutex<Option<Sender<i32>>> = Mutex::new(None);
+
+    static HOOK_THREAD: AtomicU32 = AtomicU32::new(0);
+
+    /// Starts the threads that hook the mouse and change the volume. Called once.
+    pub fn start(app: &AppHandle) {
+        let (tx, rx) = mpsc::channel();
+        *WHEEL.lock().unwrap() = Some(tx);
+
+        let handle = app.clone();
+        std::thread::spawn(move || {
+            // Precision touchpads scroll in fractions of a notch
+            let mut delta = 0;
+            for movement in rx {
+                delta += movement;
+                let notches = delta / WHEEL_DELTA as i32;
+                if notches != 0 {
+                    delta -= notches * WHEEL_DELTA as i32;
+                    if let Err(e) = super::step_volume(&handle, notches) {
+                        eprintln!("Failed to change volume: {}", e);
+                    }
+                }
+            }
+        });
+
+        // The hook is only installed while the cursor is over the icon, so the
+        // rest of the time mouse input doesn't go through SoundShift at all
+        let (ready_tx, ready_rx) = mpsc::channel();
+        std::thread::spawn(move || unsafe {
+            // A thread has no message queue until it asks for one, and messages
+            // posted before then are lost
+            let mut msg = MSG::default();
+            let _ = PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_NOREMOVE);
+            HOOK_THREAD.store(GetCurrentThreadId(), Ordering::SeqCst);
+            let _ = ready_tx.send(());
+
+            let mut hook: Option<HHOOK> = None;
+            while GetMessageW(&mut msg, HWND::default(), 0, 0).0 > 0 {
+                match msg.message {
+                    WM_HOOK if hook.is_none() => {
+                        hook =
+                            SetWindowsHookExW(WH_MOUSE_LL, Some(on_mouse), HINSTANCE::default(), 0)
+                                .inspect_err(|e| eprintln!("Failed to hook mouse wheel: {}", e))
+                                .ok();
+                    }
+                    WM_UNHOOK => {
+                        if let Some(hook) = hook.take() {
+                            let _ = UnhookWindowsHookEx(hook);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        // So the first hover after startup reaches the thread
+        let _ = ready_rx.recv();
+    }
+
+    /// Starts watching the wheel over the icon at `rect`, or stops for None.
+    pub fn hover(rect: Option<Rect>) {
+        // Tray rects are already in physical pixels
+        let area = rect.map(|rect| {
+            let position = rect.position.to_physical::<i32>(1.0);
+            let size = rect.size.to_physical::<i32>(1.0);
+            (
+                position.x,
+                position.y,
+                position.x + size.width,
+                position.y + size.height,
+            )
+        });
+        let message = if area.is_some() { WM_HOOK } else { WM_UNHOOK };
+        *ICON_AREA.lock().unwrap() = area;
+
+        let thread = HOOK_THREAD.load(Ordering::SeqCst);
+        if thread != 0 {
+            unsafe {
+                let _ = PostThreadMessageW(thread, message, WPARAM(0), LPARAM(0));
+            }
+        }
+    }
+
+    unsafe extern "system" fn on_mouse(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code >= 0 && wparam.0 as u32 == WM_MOUSEWHEEL {
+            let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+            // Checked here too, in case the cursor left without a leave event
+            let over_icon = ICON_AREA.lock().unwrap().map_or(false, |(l, t, r, b)| {
+                info.pt.x >= l && info.pt.x < r && info.pt.y >= t && info.pt.y < b
+            });
+
+            if over_icon {
+                // The high word is the distance moved, positive away from the user
+                let delta = (info.mouseData >> 16) as u16 as i16 as i32;
+                if let Some(wheel) = WHEEL.lock().unwrap().as_ref() {
+                    let _ = wheel.send(delta);
+                }
+                // Keep the scroll from also reaching whatever is behind the taskbar
+                return LRESULT(1);
+            }
+        }
+
+        CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+    }
+}
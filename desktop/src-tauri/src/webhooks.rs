@@ -0,0 +1,209 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener, Manager};
+
+use crate::commands;
+use crate::state::{unix_timestamp, AppState};
+
+// Attempts per delivery, waiting 1s, 2s, 4s and 8s between them
+const MAX_ATTEMPTS: u32 = 5;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Deliveries kept for the log in Settings, newest first
+const LOG_SIZE: usize = 50;
+
+static LOG: Mutex<VecDeque<Delivery>> = Mutex::new(VecDeque::new());
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+// One payload sent to one URL, updated as attempts are made
+#[derive(Debug, Clone, Serialize)]
+pub struct Delivery {
+    pub id: u64,
+    pub url: String,
+    pub event: String,
+    // Device switched to, for telling deliveries apart in the log
+    pub device: String,
+    pub at: u64,
+    pub attempts: u32,
+    // HTTP status of the last attempt, if the server answered
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub delivered: bool,
+}
+
+/// Posts every switch to the configured URLs. Called once at startup.
+pub fn start_event_relay(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any("device-switched", move |event| {
+        let switch: Value = match serde_json::from_str(event.payload()) {
+            Ok(switch) => switch,
+            Err(_) => return,
+        };
+
+        let group = match (switch["group_id"].as_str(), switch["group_name"].as_str()) {
+            (Some(id), Some(name)) => json!({ "id": id, "name": name }),
+            _ => Value::Null,
+        };
+        let payload = json!({
+            "event": "device_switched",
+            "timestamp": unix_timestamp(),
+            "device": switch["device"],
+            "previous": switch["previous"],
+            "group": group,
+            "source": switch["source"],
+        });
+
+        send(&handle, payload);
+    });
+}
+
+/// Sends a test payload, with the current default device, to every configured URL.
+pub fn send_test(app: &AppHandle) -> Result<(), String> {
+    let devices = commands::get_audio_devices(app.state())?;
+    let device = devices.iter().find(|d| d.is_default);
+
+    let payload = json!({
+        "event": "test",
+        "timestamp": unix_timestamp(),
+        "device": device,
+        "previous": null,
+        "group": null,
+        "source": "app",
+    });
+
+    if send(app, payload) == 0 {
+        return Err("No webhook URLs configured".to_string());
+    }
+    Ok(())
+}
+
+/// Recent deliveries, newest first.
+pub fn deliveries() -> Vec<Delivery> {
+    LOG.lock().unwrap().iter().cloned().collect()
+}
+
+// Starts a delivery to each URL and returns how many there were
+fn send(app: &AppHandle, payload: Value) -> usize {
+    let urls: Vec<String> = {
+        let state = app.state::<Mutex<AppState>>();
+        let state = state.lock().unwrap();
+        let webhooks = &state.settings.webhooks;
+        if !webhooks.enabled {
+            return 0;
+        }
+        webhooks
+            .urls
+            .iter()
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+            .collect()
+    };
+
+    let body = payload.to_string();
+    let device = payload["device"]["alias"]
+        .as_str()
+        .or(payload["device"]["name"].as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    for url in &urls {
+        let delivery = Delivery {
+            id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            url: url.clone(),
+            event: payload["event"].as_str().unwrap_or_default().to_string(),
+            device: device.clone(),
+            at: unix_timestamp(),
+            attempts: 0,
+            status: None,
+            error: None,
+            delivered: false,
+        };
+
+        let app = app.clone();
+        let body = body.clone();
+        // Each delivery retries on its own, so one slow server doesn't hold up the rest
+        std::thread::spawn(move || deliver(&app, delivery, &body));
+    }
+
+    urls.len()
+}
+
+fn deliver(app: &AppHandle, mut delivery: Delivery, body: &str) {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+
+    if !delivery.url.starts_with("http://") && !delivery.url.starts_with("https://") {
+        delivery.error = Some("Only http:// and https:// URLs are supported".to_string());
+        record(app, &delivery);
+        return;
+    }
+
+    loop {
+        delivery.attempts += 1;
+
+        let retry = match agent
+            .post(&delivery.url)
+            .set("Content-Type", "application/json")
+            .set(
+                "User-Agent",
+                concat!("SoundShift/", env!("CARGO_PKG_VERSION")),
+            )
+            .send_string(body)
+        {
+            Ok(response) => {
+                delivery.status = Some(response.status());
+                delivery.error = None;
+                delivery.delivered = true;
+                false
+            }
+            Err(ureq::Error::Status(code, response)) => {
+                delivery.status = Some(code);
+                delivery.error = Some(response.status_text().to_string());
+                // Other client errors won't go away by sending the same thing again
+                code >= 500 || code == 408 || code == 429
+            }
+            Err(ureq::Error::Transport(e)) => {
+                delivery.status = None;
+                delivery.error = Some(e.to_string());
+                true
+            }
+        };
+
+        record(app, &delivery);
+
+        if !retry || delivery.attempts >= MAX_ATTEMPTS {
+            break;
+        }
+        std::thread::sleep(Duration::from_secs(1 << (delivery.attempts - 1)));
+    }
+
+    if !delivery.delivered {
+        eprintln!(
+            "Webhook to {} failed after {} attempt(s): {}",
+            delivery.url,
+            delivery.attempts,
+            delivery.error.as_deref().unwrap_or("unknown error")
+        );
+    }
+}
+
+// Adds or updates the delivery in the log and tells the frontend
+fn record(app: &AppHandle, delivery: &Delivery) {
+    {
+        let mut log = LOG.lock().unwrap();
+        match log.iter_mut().find(|d| d.id == delivery.id) {
+            Some(entry) => *entry = delivery.clone(),
+            None => {
+                log.push_front(delivery.clone());
+                log.truncate(LOG_SIZE);
+            }
+        }
+    }
+
+    let _ = app.emit("webhook-delivery", delivery);
+}
@@ -3,6 +3,7 @@ import Layout from "./components/layout/Layout";
 import DevicesPage from "./pages/DevicesPage";
 import GroupsPage from "./pages/GroupsPage";
 import SettingsPage from "./pages/SettingsPage";
+import OsdPage from "./pages/OsdPage";
 
 // Redirect component that preserves query parameters
 function RedirectWithParams({ to }: { to: string }) {
@@ -11,6 +12,13 @@ function RedirectWithParams({ to }: { to: string }) {
 }
 
 function App() {
+  const location = useLocation();
+
+  // The switch overlay window shows just the overlay, without the sidebar
+  if (location.pathname === "/osd") {
+    return <OsdPage />;
+  }
+
   return (
     <Layout>
       <Routes>
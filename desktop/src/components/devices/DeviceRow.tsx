@@ -47,13 +47,18 @@ export default function DeviceRow({ device, isActive, onClick, compact = false }
           />
         </svg>
       </div>
-      <span
-        className={`flex-1 truncate ${compact ? "text-sm" : "text-sm font-medium"} ${
-          isActive ? "text-surface-100" : "text-surface-300"
-        }`}
-      >
-        {device.name}
-      </span>
+      <div className="flex-1 min-w-0">
+        <span
+          className={`block truncate ${compact ? "text-sm" : "text-sm font-medium"} ${
+            isActive ? "text-surface-100" : "text-surface-300"
+          }`}
+        >
+          {device.alias || device.name}
+        </span>
+        {device.alias && !compact && (
+          <span className="block truncate text-xs text-surface-500">{device.name}</span>
+        )}
+      </div>
       {isActive && (
         <span className="text-xs text-primary-400 font-medium">Active</span>
       )}
@@ -1,20 +1,24 @@
-import type { AudioDevice, DeviceGroup } from "../../types";
+import type { AudioDevice, DeviceGroup, InventoryEntry } from "../../types";
+import { matchesDevice } from "../../utils/deviceMatcher";
+import { formatLastSeen } from "../../utils/time";
 
 interface GroupCardProps {
   group: DeviceGroup;
   onlineDevices: AudioDevice[];
+  inventory?: InventoryEntry[];
+  aliases?: Record<string, string>;
   onEdit: () => void;
   onDelete: () => void;
   onCycle: () => void;
   onSelectDevice: (index: number) => void;
 }
 
-export default function GroupCard({ group, onlineDevices, onEdit, onDelete, onCycle, onSelectDevice }: GroupCardProps) {
-  // Create a set of online device IDs for quick lookup
-  const onlineDeviceIds = new Set(onlineDevices.map(d => d.id));
+export default function GroupCard({ group, onlineDevices, inventory = [], aliases = {}, onEdit, onDelete, onCycle, onSelectDevice }: GroupCardProps) {
+  // Count online entries in this group (pattern entries count if anything matches)
+  const onlineCount = group.devices.filter(d => onlineDevices.some(o => matchesDevice(d, o))).length;
 
-  // Count online devices in this group
-  const onlineCount = group.devices.filter(d => onlineDeviceIds.has(d.id)).length;
+  // Most recent time a device in this group was followed to a new ID
+  const lastRelink = group.relinks?.[group.relinks.length - 1];
 
   return (
     <div className="bg-surface-850 rounded-2xl border border-surface-750 p-5">
@@ -33,6 +37,12 @@ export default function GroupCard({ group, onlineDevices, onEdit, onDelete, onCy
               {onlineCount}/{group.devices.length} online
             </span>
           </div>
+          {lastRelink && (
+            <p className="text-xs text-surface-500 mt-2" title={`${lastRelink.old_id} → ${lastRelink.new_id}`}>
+              Re-linked {lastRelink.name} by {lastRelink.matched_by} on{" "}
+              {new Date(lastRelink.at * 1000).toLocaleDateString()}
+            </p>
+          )}
         </div>
         <div className="flex items-center gap-1">
           <button
@@ -69,8 +79,12 @@ export default function GroupCard({ group, onlineDevices, onEdit, onDelete, onCy
       {/* Devices in group */}
       <div className="space-y-2">
         {group.devices.map((device, index) => {
-          const isOnline = onlineDeviceIds.has(device.id);
-          const onlineDevice = onlineDevices.find(d => d.id === device.id);
+          const matches = onlineDevices.filter(d => matchesDevice(device, d));
+          const isOnline = matches.length > 0;
+          const lastSeen = inventory.find(e => e.id === device.id);
+          const onlineDevice = device.matcher
+            ? matches.find(d => d.id === group.current_device_id) ?? matches.find(d => d.is_default)
+            : matches[0];
 
           return (
             <button
@@ -95,8 +109,23 @@ export default function GroupCard({ group, onlineDevices, onEdit, onDelete, onCy
               <span className={`text-sm truncate ${
                 isOnline ? "text-surface-200" : "text-surface-500"
               }`}>
-                {device.name}
-                {!isOnline && <span className="ml-2 text-xs text-surface-600">(Offline)</span>}
+                {device.matcher ? (
+                  <>
+                    <span className="font-mono">{device.matcher.pattern}</span>
+                    <span className="ml-2 text-xs text-surface-500">
+                      {isOnline ? `${matches.length} matching` : "(No matches)"}
+                    </span>
+                  </>
+                ) : (
+                  <>
+                    {aliases[device.id] || device.name}
+                    {!isOnline && (
+                      <span className="ml-2 text-xs text-surface-600">
+                        (Offline{lastSeen ? ` · last seen ${formatLastSeen(lastSeen.last_seen)}` : ""})
+                      </span>
+                    )}
+                  </>
+                )}
               </span>
               {isOnline && index === group.current_index && (
                 <span className="ml-auto text-xs text-primary-400">Active</span>
@@ -1,5 +1,6 @@
 import { useEffect, useState } from "react";
-import type { AudioDevice, DeviceGroup, GroupDevice } from "../../types";
+import type { AudioDevice, DeviceGroup, DeviceMatcher, GroupDevice, GroupHooks } from "../../types";
+import { matchesDevice } from "../../utils/deviceMatcher";
 import ShortcutRecorder from "../shortcuts/ShortcutRecorder";
 
 interface GroupModalProps {
@@ -7,27 +8,37 @@ interface GroupModalProps {
   onClose: () => void;
   onSave: (group: Omit<DeviceGroup, "id" | "current_index"> & { id?: string }) => void;
   devices: AudioDevice[];
+  aliases?: Record<string, string>;
   editingGroup: DeviceGroup | null;
 }
 
-export default function GroupModal({ isOpen, onClose, onSave, devices, editingGroup }: GroupModalProps) {
+export default function GroupModal({ isOpen, onClose, onSave, devices, aliases = {}, editingGroup }: GroupModalProps) {
   const [name, setName] = useState("");
   const [selectedDevices, setSelectedDevices] = useState<GroupDevice[]>([]);
   const [shortcut, setShortcut] = useState<string | null>(null);
-
-  // Create a set of online device IDs for quick lookup
-  const onlineDeviceIds = new Set(devices.map(d => d.id));
+  const [pattern, setPattern] = useState("");
+  const [patternKind, setPatternKind] = useState<DeviceMatcher["kind"]>("wildcard");
+  const [hooks, setHooks] = useState<GroupHooks>({ pre_switch: "", post_switch: "" });
+  const [showHooks, setShowHooks] = useState(false);
+  const [hideNotifications, setHideNotifications] = useState(false);
 
   useEffect(() => {
     if (editingGroup) {
       setName(editingGroup.name);
       setSelectedDevices(editingGroup.devices);
       setShortcut(editingGroup.shortcut);
+      setHooks(editingGroup.hooks ?? { pre_switch: "", post_switch: "" });
+      setShowHooks(!!(editingGroup.hooks?.pre_switch || editingGroup.hooks?.post_switch));
+      setHideNotifications(editingGroup.hide_notifications ?? false);
     } else {
       setName("");
       setSelectedDevices([]);
       setShortcut(null);
+      setHooks({ pre_switch: "", post_switch: "" });
+      setShowHooks(false);
+      setHideNotifications(false);
     }
+    setPattern("");
   }, [editingGroup, isOpen]);
 
   const handleSubmit = (e: React.FormEvent) => {
@@ -39,6 +50,8 @@ export default function GroupModal({ isOpen, onClose, onSave, devices, editingGr
       name: name.trim(),
       devices: selectedDevices,
       shortcut,
+      hooks,
+      hide_notifications: hideNotifications,
     });
   };
 
@@ -48,11 +61,24 @@ export default function GroupModal({ isOpen, onClose, onSave, devices, editingGr
       if (exists) {
         return prev.filter(d => d.id !== device.id);
       } else {
-        return [...prev, { id: device.id, name: device.name }];
+        return [...prev, { id: device.id, name: device.name, container_id: device.container_id }];
       }
     });
   };
 
+  const addPattern = () => {
+    const trimmed = pattern.trim();
+    if (!trimmed) return;
+
+    const id = `pattern:${trimmed}`;
+    setSelectedDevices((prev) =>
+      prev.some(d => d.id === id)
+        ? prev
+        : [...prev, { id, name: trimmed, matcher: { kind: patternKind, pattern: trimmed } }]
+    );
+    setPattern("");
+  };
+
   const removeDevice = (deviceId: string) => {
     setSelectedDevices((prev) => prev.filter(d => d.id !== deviceId));
   };
@@ -134,22 +160,53 @@ export default function GroupModal({ isOpen, onClose, onSave, devices, editingGr
                         </svg>
                       )}
                     </div>
-                    <span className="text-sm">{device.name}</span>
+                    <span className="text-sm">{device.alias || device.name}</span>
                   </button>
                 );
               })}
             </div>
+            <div className="flex gap-2 mt-3">
+              <input
+                type="text"
+                value={pattern}
+                onChange={(e) => setPattern(e.target.value)}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") {
+                    e.preventDefault();
+                    addPattern();
+                  }
+                }}
+                placeholder={patternKind === "wildcard" ? "Match by name, e.g. *HDMI*" : "Match by regex, e.g. ^Dock"}
+                className="flex-1 min-w-0 px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500 transition-all duration-200"
+              />
+              <select
+                value={patternKind}
+                onChange={(e) => setPatternKind(e.target.value as DeviceMatcher["kind"])}
+                className="px-2 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-300 focus:outline-none focus:border-primary-500"
+              >
+                <option value="wildcard">Wildcard</option>
+                <option value="regex">Regex</option>
+              </select>
+              <button
+                type="button"
+                onClick={addPattern}
+                disabled={!pattern.trim()}
+                className="px-3 py-2 bg-surface-750 hover:bg-surface-700 disabled:opacity-40 rounded-xl text-sm text-surface-300 hover:text-surface-100 transition-all duration-200"
+              >
+                Add
+              </button>
+            </div>
           </div>
 
-          {/* Cycle Order - Only show when 2+ devices selected */}
-          {selectedDevices.length >= 2 && (
+          {/* Cycle Order - Only show when 2+ devices or a pattern are selected */}
+          {(selectedDevices.length >= 2 || selectedDevices.some(d => d.matcher)) && (
             <div>
               <label className="block text-sm font-medium text-surface-200 mb-3">
                 Cycle Order
               </label>
               <div className="bg-surface-800 rounded-xl p-2 space-y-1">
                 {selectedDevices.map((device, index) => {
-                  const isOnline = onlineDeviceIds.has(device.id);
+                  const isOnline = devices.some(d => matchesDevice(device, d));
                   return (
                     <div
                       key={device.id}
@@ -159,8 +216,12 @@ export default function GroupModal({ isOpen, onClose, onSave, devices, editingGr
                         {index + 1}
                       </span>
                       <span className={`flex-1 text-sm truncate ${isOnline ? "text-surface-200" : "text-surface-500"}`}>
-                        {device.name}
-                        {!isOnline && <span className="ml-2 text-xs text-surface-600">(Offline)</span>}
+                        {device.matcher ? <span className="font-mono">{device.matcher.pattern}</span> : aliases[device.id] || device.name}
+                        {!isOnline && (
+                          <span className="ml-2 text-xs text-surface-600">
+                            {device.matcher ? "(No matches)" : "(Offline)"}
+                          </span>
+                        )}
                       </span>
                       <div className="flex items-center">
                         <button
@@ -183,12 +244,12 @@ export default function GroupModal({ isOpen, onClose, onSave, devices, editingGr
                             <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M19 9l-7 7-7-7" />
                           </svg>
                         </button>
-                        {!isOnline && (
+                        {(!isOnline || device.matcher) && (
                           <button
                             type="button"
                             onClick={() => removeDevice(device.id)}
                             className="p-1.5 text-surface-400 hover:text-danger-400 transition-all ml-1"
-                            title="Remove offline device"
+                            title={device.matcher ? "Remove pattern" : "Remove offline device"}
                           >
                             <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                               <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M6 18L18 6M6 6l12 12" />
@@ -216,6 +277,52 @@ export default function GroupModal({ isOpen, onClose, onSave, devices, editingGr
               onChange={setShortcut}
             />
           </div>
+
+          {/* Notifications */}
+          <label className="flex items-center gap-2 text-sm text-surface-200">
+            <input
+              type="checkbox"
+              checked={!hideNotifications}
+              onChange={(e) => setHideNotifications(!e.target.checked)}
+              className="accent-primary-500"
+            />
+            Show a notification when this group switches
+          </label>
+
+          {/* Hooks */}
+          <div>
+            <button
+              type="button"
+              onClick={() => setShowHooks(!showHooks)}
+              className="flex items-center gap-1.5 text-sm font-medium text-surface-200"
+            >
+              <svg className={`w-4 h-4 transition-transform ${showHooks ? "rotate-90" : ""}`} fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Hooks <span className="text-surface-500 font-normal">(optional)</span>
+            </button>
+            {showHooks && (
+              <div className="mt-3 space-y-3">
+                {(["pre_switch", "post_switch"] as const).map((key) => (
+                  <label key={key} className="block">
+                    <span className="text-xs text-surface-400">
+                      {key === "pre_switch" ? "Before switching" : "After switching"}
+                    </span>
+                    <input
+                      type="text"
+                      value={hooks[key]}
+                      onChange={(e) => setHooks({ ...hooks, [key]: e.target.value })}
+                      placeholder="Shell command"
+                      className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm font-mono text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+                    />
+                  </label>
+                ))}
+                <p className="text-xs text-surface-500">
+                  Run after the global hooks in Settings, for switches made through this group
+                </p>
+              </div>
+            )}
+          </div>
         </form>
 
         {/* Footer */}
@@ -0,0 +1,157 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { AudioDevice, ChimeSettings, DeviceChime } from "../../types";
+import { isDemoMode, mockDevices } from "../../mocks/demoData";
+import Toggle from "./Toggle";
+
+interface ChimeSectionProps {
+  settings: ChimeSettings;
+  onChange: (settings: ChimeSettings) => void;
+}
+
+const inputClass =
+  "px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500";
+
+export default function ChimeSection({ settings, onChange }: ChimeSectionProps) {
+  const [devices, setDevices] = useState<AudioDevice[]>([]);
+  // Edited locally and saved when the field loses focus
+  const [sound, setSound] = useState(settings.sound);
+  const [volume, setVolume] = useState(settings.volume);
+  const [testError, setTestError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setSound(settings.sound);
+    setVolume(settings.volume);
+  }, [settings.sound, settings.volume]);
+
+  useEffect(() => {
+    if (isDemoMode()) {
+      setDevices(mockDevices);
+      return;
+    }
+    invoke<AudioDevice[]>("get_audio_devices").then(setDevices);
+  }, []);
+
+  // Empty overrides are dropped, so the device goes back to the global chime
+  const setDeviceChime = (deviceId: string, chime: DeviceChime) => {
+    const { [deviceId]: _, ...rest } = settings.devices;
+    const own: DeviceChime = {};
+    if (chime.sound?.trim()) own.sound = chime.sound.trim();
+    if (chime.volume !== undefined) own.volume = chime.volume;
+    onChange({
+      ...settings,
+      devices: Object.keys(own).length > 0 ? { ...rest, [deviceId]: own } : rest,
+    });
+  };
+
+  const test = async (deviceId: string) => {
+    setTestError(null);
+    if (isDemoMode()) {
+      return;
+    }
+    try {
+      await invoke("test_chime", { deviceId });
+    } catch (e) {
+      setTestError(e as string);
+    }
+  };
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">Chime</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Play a short sound on the new device after switching, so you can hear which one is active
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <label className="block">
+            <span className="text-xs text-surface-400">Sound file</span>
+            <input
+              type="text"
+              value={sound}
+              onChange={(e) => setSound(e.target.value)}
+              onBlur={() => sound !== settings.sound && onChange({ ...settings, sound: sound.trim() })}
+              placeholder="Built-in chime"
+              className={`mt-1 w-full font-mono ${inputClass}`}
+            />
+            <span className="block text-xs text-surface-500 mt-1">Full path to a WAV, MP3, FLAC or Ogg file</span>
+          </label>
+
+          <label className="block">
+            <span className="text-xs text-surface-400">Volume: {volume}%</span>
+            <input
+              type="range"
+              min={0}
+              max={100}
+              value={volume}
+              onChange={(e) => setVolume(Number(e.target.value))}
+              onPointerUp={() => volume !== settings.volume && onChange({ ...settings, volume })}
+              onKeyUp={() => volume !== settings.volume && onChange({ ...settings, volume })}
+              className="mt-1 w-full accent-primary-500"
+            />
+          </label>
+
+          {devices.length > 0 && (
+            <div>
+              <p className="text-surface-100 text-sm font-medium">Per device</p>
+              <p className="text-xs text-surface-500 mt-0.5">Leave blank to use the sound and volume above</p>
+              <ul className="mt-2 space-y-2">
+                {devices.map((device) => {
+                  const own = settings.devices[device.id] ?? {};
+                  return (
+                    <li key={device.id} className="flex items-center gap-2">
+                      <span className="flex-1 min-w-0 text-sm text-surface-300 truncate">
+                        {device.alias ?? device.name}
+                      </span>
+                      <input
+                        type="text"
+                        defaultValue={own.sound ?? ""}
+                        key={`${device.id}-${own.sound ?? ""}`}
+                        onBlur={(e) => {
+                          if (e.target.value.trim() !== (own.sound ?? "")) {
+                            setDeviceChime(device.id, { ...own, sound: e.target.value });
+                          }
+                        }}
+                        placeholder="Sound file"
+                        className={`w-48 font-mono ${inputClass}`}
+                      />
+                      <input
+                        type="number"
+                        min={0}
+                        max={100}
+                        defaultValue={own.volume ?? ""}
+                        key={`${device.id}-${own.volume ?? ""}`}
+                        onBlur={(e) => {
+                          const value = e.target.value === "" ? undefined : Math.min(100, Math.max(0, Math.round(Number(e.target.value))));
+                          if (value !== own.volume) {
+                            setDeviceChime(device.id, { ...own, volume: value });
+                          }
+                        }}
+                        placeholder="%"
+                        className={`w-20 ${inputClass}`}
+                      />
+                      <button
+                        onClick={() => test(device.id)}
+                        className="px-3 py-2 bg-surface-750 hover:bg-surface-700 rounded-xl transition-all duration-200 text-xs text-surface-300 hover:text-surface-100"
+                      >
+                        Test
+                      </button>
+                    </li>
+                  );
+                })}
+              </ul>
+            </div>
+          )}
+
+          {testError && <p className="text-xs text-red-400">{testError}</p>}
+        </>
+      )}
+    </div>
+  );
+}
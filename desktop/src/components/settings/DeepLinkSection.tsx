@@ -0,0 +1,42 @@
+import type { DeepLinkSettings } from "../../types";
+import Toggle from "./Toggle";
+
+interface DeepLinkSectionProps {
+  settings: DeepLinkSettings;
+  onChange: (settings: DeepLinkSettings) => void;
+}
+
+export default function DeepLinkSection({ settings, onChange }: DeepLinkSectionProps) {
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">Links</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Follow <span className="font-mono">soundshift://</span> links from wikis, documents and launchers
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <div className="flex items-center justify-between py-1">
+            <div>
+              <p className="text-surface-100 text-sm font-medium">Ask before switching</p>
+              <p className="text-xs text-surface-500 mt-0.5">
+                Any web page can open a link, so only turn this off if you trust where yours come from
+              </p>
+            </div>
+            <Toggle checked={settings.confirm} onChange={(confirm) => onChange({ ...settings, confirm })} />
+          </div>
+
+          <p className="text-xs text-surface-500 leading-relaxed">
+            For example <span className="font-mono">soundshift://cycle?group=Desk</span> or{" "}
+            <span className="font-mono">soundshift://select?device=Headphones</span>.
+          </p>
+        </>
+      )}
+    </div>
+  );
+}
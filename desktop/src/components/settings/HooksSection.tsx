@@ -0,0 +1,137 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { HookRun, HookSettings } from "../../types";
+import { isDemoMode } from "../../mocks/demoData";
+
+interface HooksSectionProps {
+  settings: HookSettings;
+  onChange: (settings: HookSettings) => void;
+}
+
+export default function HooksSection({ settings, onChange }: HooksSectionProps) {
+  // Edited locally and saved together, so half-typed commands never run
+  const [draft, setDraft] = useState(settings);
+  const [runs, setRuns] = useState<HookRun[]>([]);
+
+  useEffect(() => {
+    setDraft(settings);
+  }, [settings]);
+
+  useEffect(() => {
+    // Skip event listener in demo mode (Tauri APIs not available in browser)
+    if (isDemoMode()) {
+      return;
+    }
+
+    invoke<HookRun[]>("get_hook_runs").then(setRuns);
+
+    const unlisten = listen<HookRun>("hook-run", (event) => {
+      setRuns((current) => [event.payload, ...current].slice(0, 50));
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const isDirty = JSON.stringify(draft) !== JSON.stringify(settings);
+
+  const field = (label: string, key: "pre_switch" | "post_switch") => (
+    <label className="block">
+      <span className="text-xs text-surface-400">{label}</span>
+      <input
+        type="text"
+        value={draft[key]}
+        onChange={(e) => setDraft({ ...draft, [key]: e.target.value })}
+        placeholder="Shell command"
+        className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm font-mono text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+      />
+    </label>
+  );
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div>
+        <h3 className="text-base font-medium text-surface-100">Hooks</h3>
+        <p className="text-xs text-surface-500 mt-0.5">
+          Run commands around every switch, e.g. to restart an app or toggle an EQ
+        </p>
+      </div>
+
+      {field("Before switching", "pre_switch")}
+      {field("After switching", "post_switch")}
+
+      <label className="block w-32">
+        <span className="text-xs text-surface-400">Timeout (seconds)</span>
+        <input
+          type="number"
+          min={1}
+          value={draft.timeout_secs}
+          onChange={(e) => setDraft({ ...draft, timeout_secs: Number(e.target.value) })}
+          className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 focus:outline-none focus:border-primary-500"
+        />
+      </label>
+
+      <p className="text-xs text-surface-500 leading-relaxed">
+        Commands get <span className="font-mono">SOUNDSHIFT_OLD_DEVICE_ID</span>,{" "}
+        <span className="font-mono">SOUNDSHIFT_OLD_DEVICE_NAME</span>, <span className="font-mono">SOUNDSHIFT_NEW_DEVICE_ID</span>,{" "}
+        <span className="font-mono">SOUNDSHIFT_NEW_DEVICE_NAME</span>, <span className="font-mono">SOUNDSHIFT_GROUP_ID</span> and{" "}
+        <span className="font-mono">SOUNDSHIFT_GROUP_NAME</span>. The switch waits for the before hook, so keep it quick.
+      </p>
+
+      {isDirty && (
+        <div className="flex justify-end gap-2">
+          <button
+            onClick={() => setDraft(settings)}
+            className="px-4 py-2 text-sm text-surface-400 hover:text-surface-100 transition-colors"
+          >
+            Cancel
+          </button>
+          <button
+            onClick={() => onChange(draft)}
+            disabled={!Number.isInteger(draft.timeout_secs) || draft.timeout_secs < 1}
+            className="px-4 py-2 bg-primary-500 hover:bg-primary-400 disabled:opacity-40 disabled:cursor-not-allowed rounded-xl transition-all duration-200 text-sm font-medium text-white"
+          >
+            Save
+          </button>
+        </div>
+      )}
+
+      {runs.length > 0 && (
+        <div>
+          <p className="text-surface-100 text-sm font-medium">Recent runs</p>
+          <ul className="mt-2 space-y-1 max-h-48 overflow-y-auto">
+            {runs.map((run) => (
+              <li key={run.id} className="text-xs">
+                <div className="flex items-center gap-3">
+                  <span className={run.success ? "text-primary-400" : "text-red-400"}>
+                    {run.success
+                      ? "Finished"
+                      : run.error
+                        ? "Couldn't start"
+                        : run.timed_out
+                          ? "Timed out"
+                          : `Exit ${run.exit_code ?? "?"}`}
+                  </span>
+                  <span className="flex-1 text-surface-300 font-mono truncate" title={run.error ?? run.command}>
+                    {run.command}
+                  </span>
+                  <span className="text-surface-500">
+                    {run.stage === "pre_switch" ? "Before" : "After"} {run.device}
+                  </span>
+                  <span className="text-surface-500">{new Date(run.at * 1000).toLocaleTimeString()}</span>
+                </div>
+                {run.output.length > 0 && (
+                  <pre className="mt-1 px-2 py-1 bg-surface-800 rounded-lg text-surface-400 font-mono whitespace-pre-wrap break-all">
+                    {run.output.join("\n")}
+                  </pre>
+                )}
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,126 @@
+import { useEffect, useState } from "react";
+import type { HttpApiSettings } from "../../types";
+import Toggle from "./Toggle";
+
+interface HttpApiSectionProps {
+  settings: HttpApiSettings;
+  onChange: (settings: HttpApiSettings) => void;
+}
+
+export default function HttpApiSection({ settings, onChange }: HttpApiSectionProps) {
+  const [port, setPort] = useState(String(settings.port));
+  const [copied, setCopied] = useState(false);
+  // One origin per line, saved together
+  const [origins, setOrigins] = useState(settings.allowed_origins.join("\n"));
+
+  useEffect(() => {
+    setPort(String(settings.port));
+  }, [settings.port]);
+
+  useEffect(() => {
+    setOrigins(settings.allowed_origins.join("\n"));
+  }, [settings.allowed_origins]);
+
+  const commitOrigins = () => {
+    const allowed = origins
+      .split("\n")
+      .map((origin) => origin.trim())
+      .filter((origin) => origin.length > 0);
+    if (JSON.stringify(allowed) !== JSON.stringify(settings.allowed_origins)) {
+      onChange({ ...settings, allowed_origins: allowed });
+    }
+  };
+
+  const commitPort = () => {
+    const value = Number(port);
+    if (Number.isInteger(value) && value > 0 && value < 65536) {
+      if (value !== settings.port) {
+        onChange({ ...settings, port: value });
+      }
+    } else {
+      setPort(String(settings.port));
+    }
+  };
+
+  const copyToken = async () => {
+    await navigator.clipboard.writeText(settings.token);
+    setCopied(true);
+    setTimeout(() => setCopied(false), 1500);
+  };
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">HTTP API</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Control SoundShift from Stream Deck, Touch Portal or a dashboard on this computer
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <div className="flex items-center justify-between py-1">
+            <div>
+              <p className="text-surface-100 text-sm font-medium">Port</p>
+              <p className="text-xs text-surface-500 mt-0.5 font-mono">http://127.0.0.1:{settings.port}/api</p>
+            </div>
+            <input
+              type="number"
+              min={1}
+              max={65535}
+              value={port}
+              onChange={(e) => setPort(e.target.value)}
+              onBlur={commitPort}
+              onKeyDown={(e) => e.key === "Enter" && commitPort()}
+              className="w-24 px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 focus:outline-none focus:border-primary-500"
+            />
+          </div>
+
+          <div>
+            <p className="text-surface-100 text-sm font-medium">Token</p>
+            <p className="text-xs text-surface-500 mt-0.5">
+              Send as <span className="font-mono">Authorization: Bearer &lt;token&gt;</span> or <span className="font-mono">?token=</span>
+            </p>
+            <div className="flex items-center gap-2 mt-2">
+              <code className="flex-1 px-3 py-2 bg-surface-800 rounded-xl text-xs text-surface-300 font-mono break-all">
+                {settings.token || "Generating…"}
+              </code>
+              <button
+                onClick={copyToken}
+                disabled={!settings.token}
+                className="px-3 py-2 bg-surface-750 hover:bg-surface-700 rounded-xl transition-all duration-200 text-xs text-surface-300 hover:text-surface-100 disabled:opacity-40"
+              >
+                {copied ? "Copied" : "Copy"}
+              </button>
+              <button
+                onClick={() => onChange({ ...settings, token: "" })}
+                className="px-3 py-2 bg-surface-750 hover:bg-surface-700 rounded-xl transition-all duration-200 text-xs text-surface-300 hover:text-surface-100"
+                title="Clients using the old token will stop working"
+              >
+                Regenerate
+              </button>
+            </div>
+          </div>
+
+          <label className="block">
+            <p className="text-surface-100 text-sm font-medium">Allowed web pages</p>
+            <p className="text-xs text-surface-500 mt-0.5">
+              Origins of browser dashboards that may call the API, one per line. Apps that aren't web pages don't need one
+            </p>
+            <textarea
+              value={origins}
+              onChange={(e) => setOrigins(e.target.value)}
+              onBlur={commitOrigins}
+              rows={2}
+              placeholder="http://localhost:3000"
+              className="mt-2 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm font-mono text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+            />
+          </label>
+        </>
+      )}
+    </div>
+  );
+}
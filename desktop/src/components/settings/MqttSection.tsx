@@ -0,0 +1,94 @@
+import { useEffect, useState } from "react";
+import type { MqttSettings } from "../../types";
+import Toggle from "./Toggle";
+
+interface MqttSectionProps {
+  settings: MqttSettings;
+  onChange: (settings: MqttSettings) => void;
+}
+
+export default function MqttSection({ settings, onChange }: MqttSectionProps) {
+  // Edited locally and saved together, so the connection isn't restarted on every keystroke
+  const [draft, setDraft] = useState(settings);
+
+  useEffect(() => {
+    setDraft(settings);
+  }, [settings]);
+
+  const isDirty = JSON.stringify(draft) !== JSON.stringify(settings);
+  const base = draft.base_topic.trim() || "soundshift";
+
+  const field = (label: string, key: "host" | "username" | "password" | "base_topic", type = "text") => (
+    <label className="block">
+      <span className="text-xs text-surface-400">{label}</span>
+      <input
+        type={type}
+        value={draft[key]}
+        onChange={(e) => setDraft({ ...draft, [key]: e.target.value })}
+        className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+      />
+    </label>
+  );
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">MQTT</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Publish devices to a broker and take commands from Home Assistant or Node-RED
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <div className="grid grid-cols-[1fr_6rem] gap-3">
+            {field("Broker host", "host")}
+            <label className="block">
+              <span className="text-xs text-surface-400">Port</span>
+              <input
+                type="number"
+                min={1}
+                max={65535}
+                value={draft.port}
+                onChange={(e) => setDraft({ ...draft, port: Number(e.target.value) })}
+                className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 focus:outline-none focus:border-primary-500"
+              />
+            </label>
+          </div>
+          <div className="grid grid-cols-2 gap-3">
+            {field("Username", "username")}
+            {field("Password", "password", "password")}
+          </div>
+          {field("Base topic", "base_topic")}
+
+          <p className="text-xs text-surface-500 leading-relaxed">
+            Publishes <span className="font-mono">{base}/default</span>, <span className="font-mono">{base}/devices</span> and{" "}
+            <span className="font-mono">{base}/status</span>. Listens on <span className="font-mono">{base}/set</span>,{" "}
+            <span className="font-mono">{base}/cycle</span> and <span className="font-mono">{base}/select</span>.
+          </p>
+
+          {isDirty && (
+            <div className="flex justify-end gap-2">
+              <button
+                onClick={() => setDraft(settings)}
+                className="px-4 py-2 text-sm text-surface-400 hover:text-surface-100 transition-colors"
+              >
+                Cancel
+              </button>
+              <button
+                onClick={() => onChange(draft)}
+                disabled={!draft.host.trim() || !Number.isInteger(draft.port) || draft.port < 1 || draft.port > 65535}
+                className="px-4 py-2 bg-primary-500 hover:bg-primary-400 disabled:opacity-40 disabled:cursor-not-allowed rounded-xl transition-all duration-200 text-sm font-medium text-white"
+              >
+                Save
+              </button>
+            </div>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
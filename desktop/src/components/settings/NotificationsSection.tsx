@@ -0,0 +1,73 @@
+import { useEffect, useState } from "react";
+import type { NotificationSettings } from "../../types";
+import Toggle from "./Toggle";
+
+interface NotificationsSectionProps {
+  settings: NotificationSettings;
+  onChange: (settings: NotificationSettings) => void;
+}
+
+export default function NotificationsSection({ settings, onChange }: NotificationsSectionProps) {
+  const [template, setTemplate] = useState(settings.template);
+
+  useEffect(() => {
+    setTemplate(settings.template);
+  }, [settings.template]);
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">Notifications</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Show a notification when a group switches. Groups can also turn theirs off.
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <label className="block">
+            <span className="text-xs text-surface-400">Message</span>
+            <input
+              type="text"
+              value={template}
+              onChange={(e) => setTemplate(e.target.value)}
+              onBlur={() => template !== settings.template && onChange({ ...settings, template })}
+              placeholder="Switched to {device}"
+              className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm font-mono text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+            />
+            <span className="block text-xs text-surface-500 mt-1">
+              <span className="font-mono">{"{device}"}</span>, <span className="font-mono">{"{group}"}</span> and{" "}
+              <span className="font-mono">{"{volume}"}</span> are filled in
+            </span>
+          </label>
+
+          <div className="flex items-center justify-between py-1">
+            <div>
+              <p className="text-surface-100 text-sm font-medium">Undo and Next buttons</p>
+              <p className="text-xs text-surface-500 mt-0.5">
+                Go back to the previous device, or on to the group's next one, while the notification is on screen (Windows and Linux)
+              </p>
+            </div>
+            <Toggle checked={settings.actions} onChange={(actions) => onChange({ ...settings, actions })} />
+          </div>
+
+          <div className="flex items-center justify-between py-1">
+            <div>
+              <p className="text-surface-100 text-sm font-medium">Hide during fullscreen apps</p>
+              <p className="text-xs text-surface-500 mt-0.5">
+                No notifications while a game, video or presentation is fullscreen
+              </p>
+            </div>
+            <Toggle
+              checked={settings.suppress_fullscreen}
+              onChange={(suppressFullscreen) => onChange({ ...settings, suppress_fullscreen: suppressFullscreen })}
+            />
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
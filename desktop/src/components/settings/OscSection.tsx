@@ -0,0 +1,123 @@
+import { useEffect, useState } from "react";
+import type { OscSettings } from "../../types";
+import Toggle from "./Toggle";
+
+interface OscSectionProps {
+  settings: OscSettings;
+  onChange: (settings: OscSettings) => void;
+}
+
+export default function OscSection({ settings, onChange }: OscSectionProps) {
+  // Edited locally and saved together, so the listener isn't rebound on every keystroke
+  const [draft, setDraft] = useState(settings);
+
+  useEffect(() => {
+    setDraft(settings);
+  }, [settings]);
+
+  const isDirty = JSON.stringify(draft) !== JSON.stringify(settings);
+  const allowedHosts = draft.allowed_hosts.map((host) => host.trim()).filter((host) => host.length > 0);
+  const invalidHosts = allowedHosts.filter((host) => !/^[0-9a-fA-F.:]+$/.test(host));
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">OSC</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Switch devices from TouchOSC, Open Stage Control or a lighting desk
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <div className="grid grid-cols-[1fr_6rem] gap-3">
+            <label className="block">
+              <span className="text-xs text-surface-400">Listen on</span>
+              <input
+                type="text"
+                value={draft.bind_address}
+                placeholder="127.0.0.1"
+                onChange={(e) => setDraft({ ...draft, bind_address: e.target.value })}
+                className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+              />
+            </label>
+            <label className="block">
+              <span className="text-xs text-surface-400">Port</span>
+              <input
+                type="number"
+                min={1}
+                max={65535}
+                value={draft.port}
+                onChange={(e) => setDraft({ ...draft, port: Number(e.target.value) })}
+                className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 focus:outline-none focus:border-primary-500"
+              />
+            </label>
+          </div>
+
+          <label className="block">
+            <span className="text-xs text-surface-400">Only accept messages from, one IP address per line</span>
+            <textarea
+              value={draft.allowed_hosts.join("\n")}
+              onChange={(e) => setDraft({ ...draft, allowed_hosts: e.target.value.split("\n") })}
+              rows={2}
+              placeholder="Any host that can reach SoundShift"
+              className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm font-mono text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+            />
+          </label>
+
+          {invalidHosts.length > 0 && (
+            <p className="text-xs text-red-400">
+              Not IP addresses: <span className="font-mono break-all">{invalidHosts.join(", ")}</span>
+            </p>
+          )}
+
+          <label className="block">
+            <span className="text-xs text-surface-400">Send feedback to</span>
+            <input
+              type="text"
+              value={draft.feedback_address}
+              placeholder="192.168.1.20:9001"
+              onChange={(e) => setDraft({ ...draft, feedback_address: e.target.value })}
+              className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+            />
+          </label>
+
+          <p className="text-xs text-surface-500 leading-relaxed">
+            Listens for <span className="font-mono">/soundshift/group/&lt;name&gt;/cycle</span>,{" "}
+            <span className="font-mono">/soundshift/group/&lt;name&gt;/select</span> and{" "}
+            <span className="font-mono">/soundshift/device/select &lt;name&gt;</span>, without authentication. Use{" "}
+            <span className="font-mono">0.0.0.0</span> to take messages from a phone or tablet, ideally with the
+            allowed addresses filled in.
+          </p>
+
+          {isDirty && (
+            <div className="flex justify-end gap-2">
+              <button
+                onClick={() => setDraft(settings)}
+                className="px-4 py-2 text-sm text-surface-400 hover:text-surface-100 transition-colors"
+              >
+                Cancel
+              </button>
+              <button
+                onClick={() => onChange({ ...draft, bind_address: draft.bind_address.trim(), allowed_hosts: allowedHosts })}
+                disabled={
+                  !Number.isInteger(draft.port) ||
+                  draft.port < 1 ||
+                  draft.port > 65535 ||
+                  draft.bind_address.trim().length === 0 ||
+                  invalidHosts.length > 0
+                }
+                className="px-4 py-2 bg-primary-500 hover:bg-primary-400 disabled:opacity-40 disabled:cursor-not-allowed rounded-xl transition-all duration-200 text-sm font-medium text-white"
+              >
+                Save
+              </button>
+            </div>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
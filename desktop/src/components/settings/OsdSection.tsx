@@ -0,0 +1,110 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { OsdPosition, OsdSettings } from "../../types";
+import { isDemoMode } from "../../mocks/demoData";
+import Toggle from "./Toggle";
+
+interface OsdSectionProps {
+  settings: OsdSettings;
+  onChange: (settings: OsdSettings) => void;
+}
+
+const positions: { value: OsdPosition; label: string }[] = [
+  { value: "top_left", label: "Top left" },
+  { value: "top", label: "Top" },
+  { value: "top_right", label: "Top right" },
+  { value: "center", label: "Center" },
+  { value: "bottom_left", label: "Bottom left" },
+  { value: "bottom", label: "Bottom" },
+  { value: "bottom_right", label: "Bottom right" },
+];
+
+export default function OsdSection({ settings, onChange }: OsdSectionProps) {
+  // In seconds while editing; saved as milliseconds
+  const [seconds, setSeconds] = useState(settings.duration_ms / 1000);
+  const [previewError, setPreviewError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setSeconds(settings.duration_ms / 1000);
+  }, [settings.duration_ms]);
+
+  const durationValid = seconds >= 0.5 && seconds <= 10;
+
+  const preview = async () => {
+    setPreviewError(null);
+    if (isDemoMode()) {
+      return;
+    }
+    try {
+      await invoke("preview_osd");
+    } catch (e) {
+      setPreviewError(e as string);
+    }
+  };
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">On-screen display</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Briefly show the new device, its group and volume over everything else, including games
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <div className="flex items-center justify-between gap-4">
+            <p className="text-surface-100 text-sm font-medium">Position</p>
+            <select
+              value={settings.position}
+              onChange={(e) => onChange({ ...settings, position: e.target.value as OsdPosition })}
+              className="px-2 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-300 focus:outline-none focus:border-primary-500"
+            >
+              {positions.map((position) => (
+                <option key={position.value} value={position.value}>
+                  {position.label}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="flex items-end gap-2">
+            <label className="block w-32">
+              <span className="text-xs text-surface-400">Shown for (seconds)</span>
+              <input
+                type="number"
+                min={0.5}
+                max={10}
+                step={0.5}
+                value={seconds}
+                onChange={(e) => setSeconds(Number(e.target.value))}
+                className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 focus:outline-none focus:border-primary-500"
+              />
+            </label>
+            {seconds * 1000 !== settings.duration_ms && (
+              <button
+                onClick={() => onChange({ ...settings, duration_ms: Math.round(seconds * 1000) })}
+                disabled={!durationValid}
+                className="px-4 py-2 bg-primary-500 hover:bg-primary-400 disabled:opacity-40 disabled:cursor-not-allowed rounded-xl transition-all duration-200 text-sm font-medium text-white"
+              >
+                Save
+              </button>
+            )}
+            <div className="flex-1" />
+            <button
+              onClick={preview}
+              className="px-3 py-2 bg-surface-750 hover:bg-surface-700 rounded-xl transition-all duration-200 text-xs text-surface-300 hover:text-surface-100"
+            >
+              Preview
+            </button>
+          </div>
+
+          {previewError && <p className="text-xs text-red-400">{previewError}</p>}
+        </>
+      )}
+    </div>
+  );
+}
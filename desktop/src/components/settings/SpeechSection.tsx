@@ -0,0 +1,66 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { SpeechSettings } from "../../types";
+import { isDemoMode } from "../../mocks/demoData";
+import Toggle from "./Toggle";
+
+interface SpeechSectionProps {
+  settings: SpeechSettings;
+  onChange: (settings: SpeechSettings) => void;
+}
+
+export default function SpeechSection({ settings, onChange }: SpeechSectionProps) {
+  const [testError, setTestError] = useState<string | null>(null);
+
+  const test = async () => {
+    setTestError(null);
+    if (isDemoMode()) {
+      return;
+    }
+    try {
+      await invoke("test_announcement");
+    } catch (e) {
+      setTestError(e as string);
+    }
+  };
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">Announcements</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Say the new device's name or alias out loud on it after switching, using the Windows voice
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <div className="flex items-center justify-between py-1">
+            <div>
+              <p className="text-surface-100 text-sm font-medium">Instead of notifications</p>
+              <p className="text-xs text-surface-500 mt-0.5">Skip the notification when the announcement is said</p>
+            </div>
+            <Toggle
+              checked={settings.replace_notification}
+              onChange={(replaceNotification) => onChange({ ...settings, replace_notification: replaceNotification })}
+            />
+          </div>
+
+          <div className="flex justify-end">
+            <button
+              onClick={test}
+              className="px-3 py-2 bg-surface-750 hover:bg-surface-700 rounded-xl transition-all duration-200 text-xs text-surface-300 hover:text-surface-100"
+            >
+              Say current device
+            </button>
+          </div>
+
+          {testError && <p className="text-xs text-red-400">{testError}</p>}
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,23 @@
+interface ToggleProps {
+  checked: boolean;
+  onChange: (checked: boolean) => void;
+  disabled?: boolean;
+}
+
+export default function Toggle({ checked, onChange, disabled = false }: ToggleProps) {
+  return (
+    <button
+      onClick={() => onChange(!checked)}
+      disabled={disabled}
+      className={`relative flex-shrink-0 w-12 h-7 rounded-full transition-all duration-200 disabled:opacity-40 ${
+        checked ? "bg-primary-500" : "bg-surface-700"
+      }`}
+    >
+      <span
+        className={`absolute top-1 left-1 w-5 h-5 bg-white rounded-full shadow-sm transition-all duration-200 ${
+          checked ? "translate-x-5" : ""
+        }`}
+      />
+    </button>
+  );
+}
@@ -0,0 +1,127 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { DeviceGroup, TrayAction, TraySettings } from "../../types";
+import { isDemoMode, mockGroups } from "../../mocks/demoData";
+import Toggle from "./Toggle";
+
+interface TraySectionProps {
+  settings: TraySettings;
+  onChange: (settings: TraySettings) => void;
+}
+
+const selectClass =
+  "px-2 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-300 focus:outline-none focus:border-primary-500";
+
+export default function TraySection({ settings, onChange }: TraySectionProps) {
+  const [groups, setGroups] = useState<DeviceGroup[]>([]);
+  const [step, setStep] = useState(settings.volume_step);
+
+  useEffect(() => {
+    setStep(settings.volume_step);
+  }, [settings.volume_step]);
+
+  useEffect(() => {
+    if (isDemoMode()) {
+      setGroups(mockGroups);
+      return;
+    }
+    invoke<DeviceGroup[]>("get_groups").then(setGroups);
+  }, []);
+
+  const actionPicker = (label: string, key: "left_click" | "middle_click") => {
+    const action = settings[key];
+
+    const pick = (value: string) => {
+      let next: TrayAction;
+      if (value === "cycle_group") {
+        // Start with the first group, the user can change it next to this
+        next = { action: "cycle_group", group_id: groups[0]?.id ?? "" };
+      } else {
+        next = { action: value } as TrayAction;
+      }
+      onChange({ ...settings, [key]: next });
+    };
+
+    return (
+      <div className="flex items-center justify-between gap-4">
+        <p className="text-surface-100 text-sm font-medium">{label}</p>
+        <div className="flex gap-2">
+          <select value={action.action} onChange={(e) => pick(e.target.value)} className={selectClass}>
+            <option value="show_window">Open window</option>
+            <option value="cycle_group" disabled={groups.length === 0}>
+              Cycle a group
+            </option>
+            <option value="toggle_mute">Mute or unmute</option>
+            <option value="nothing">Nothing</option>
+          </select>
+          {action.action === "cycle_group" && (
+            <select
+              value={action.group_id}
+              onChange={(e) => onChange({ ...settings, [key]: { action: "cycle_group", group_id: e.target.value } })}
+              className={selectClass}
+            >
+              {!groups.some((g) => g.id === action.group_id) && <option value={action.group_id}>Deleted group</option>}
+              {groups.map((group) => (
+                <option key={group.id} value={group.id}>
+                  {group.name}
+                </option>
+              ))}
+            </select>
+          )}
+        </div>
+      </div>
+    );
+  };
+
+  const stepValid = Number.isInteger(step) && step >= 1 && step <= 100;
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div>
+        <h3 className="text-base font-medium text-surface-100">Tray icon</h3>
+        <p className="text-xs text-surface-500 mt-0.5">
+          Choose what clicking the icon does. Right-click always opens the menu.
+        </p>
+      </div>
+
+      {actionPicker("Left click", "left_click")}
+      {actionPicker("Middle click", "middle_click")}
+
+      <div className="flex items-center justify-between">
+        <div>
+          <p className="text-surface-100 text-sm font-medium">Scroll to change volume</p>
+          <p className="text-xs text-surface-500 mt-0.5">Use the mouse wheel over the icon. Windows only.</p>
+        </div>
+        <Toggle
+          checked={settings.scroll_volume}
+          onChange={(scrollVolume) => onChange({ ...settings, scroll_volume: scrollVolume })}
+        />
+      </div>
+
+      {settings.scroll_volume && (
+        <div className="flex items-end gap-2">
+          <label className="block w-32">
+            <span className="text-xs text-surface-400">Step (%)</span>
+            <input
+              type="number"
+              min={1}
+              max={100}
+              value={step}
+              onChange={(e) => setStep(Number(e.target.value))}
+              className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm text-surface-100 focus:outline-none focus:border-primary-500"
+            />
+          </label>
+          {step !== settings.volume_step && (
+            <button
+              onClick={() => onChange({ ...settings, volume_step: step })}
+              disabled={!stepValid}
+              className="px-4 py-2 bg-primary-500 hover:bg-primary-400 disabled:opacity-40 disabled:cursor-not-allowed rounded-xl transition-all duration-200 text-sm font-medium text-white"
+            >
+              Save
+            </button>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
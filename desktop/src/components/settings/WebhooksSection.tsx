@@ -0,0 +1,157 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { WebhookDelivery, WebhookSettings } from "../../types";
+import { isDemoMode } from "../../mocks/demoData";
+import Toggle from "./Toggle";
+
+interface WebhooksSectionProps {
+  settings: WebhookSettings;
+  onChange: (settings: WebhookSettings) => void;
+}
+
+export default function WebhooksSection({ settings, onChange }: WebhooksSectionProps) {
+  // One URL per line, saved together
+  const [draft, setDraft] = useState(settings.urls.join("\n"));
+  const [deliveries, setDeliveries] = useState<WebhookDelivery[]>([]);
+  const [testError, setTestError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setDraft(settings.urls.join("\n"));
+  }, [settings.urls]);
+
+  useEffect(() => {
+    // Skip event listener in demo mode (Tauri APIs not available in browser)
+    if (isDemoMode()) {
+      return;
+    }
+
+    invoke<WebhookDelivery[]>("get_webhook_deliveries").then(setDeliveries);
+
+    // Each attempt updates its delivery in place, new deliveries go on top
+    const unlisten = listen<WebhookDelivery>("webhook-delivery", (event) => {
+      setDeliveries((current) => {
+        const rest = current.filter((d) => d.id !== event.payload.id);
+        return [event.payload, ...rest]
+          .sort((a, b) => b.id - a.id)
+          .slice(0, 50);
+      });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const urls = draft
+    .split("\n")
+    .map((url) => url.trim())
+    .filter((url) => url.length > 0);
+  const isDirty = JSON.stringify(urls) !== JSON.stringify(settings.urls);
+  const invalid = urls.filter((url) => !/^https?:\/\/\S+$/.test(url));
+
+  const sendTest = async () => {
+    setTestError(null);
+    try {
+      await invoke("send_test_webhook");
+    } catch (e) {
+      setTestError(e as string);
+    }
+  };
+
+  return (
+    <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-5">
+      <div className="flex items-center justify-between">
+        <div>
+          <h3 className="text-base font-medium text-surface-100">Webhooks</h3>
+          <p className="text-xs text-surface-500 mt-0.5">
+            Post every switch as JSON to Home Assistant, Node-RED, n8n or your own server
+          </p>
+        </div>
+        <Toggle checked={settings.enabled} onChange={(enabled) => onChange({ ...settings, enabled })} />
+      </div>
+
+      {settings.enabled && (
+        <>
+          <label className="block">
+            <span className="text-xs text-surface-400">URLs, one per line</span>
+            <textarea
+              value={draft}
+              onChange={(e) => setDraft(e.target.value)}
+              rows={3}
+              placeholder="http://homeassistant.local:8123/api/webhook/soundshift"
+              className="mt-1 w-full px-3 py-2 bg-surface-800 border border-surface-700 rounded-xl text-sm font-mono text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+            />
+          </label>
+
+          {invalid.length > 0 && (
+            <p className="text-xs text-red-400">
+              Only http:// and https:// URLs can be used: <span className="font-mono break-all">{invalid.join(", ")}</span>
+            </p>
+          )}
+
+          <div className="flex justify-end gap-2">
+            {isDirty ? (
+              <>
+                <button
+                  onClick={() => setDraft(settings.urls.join("\n"))}
+                  className="px-4 py-2 text-sm text-surface-400 hover:text-surface-100 transition-colors"
+                >
+                  Cancel
+                </button>
+                <button
+                  onClick={() => onChange({ ...settings, urls })}
+                  disabled={invalid.length > 0}
+                  className="px-4 py-2 bg-primary-500 hover:bg-primary-400 disabled:opacity-40 disabled:cursor-not-allowed rounded-xl transition-all duration-200 text-sm font-medium text-white"
+                >
+                  Save
+                </button>
+              </>
+            ) : (
+              <button
+                onClick={sendTest}
+                disabled={settings.urls.length === 0}
+                className="px-3 py-2 bg-surface-750 hover:bg-surface-700 rounded-xl transition-all duration-200 text-xs text-surface-300 hover:text-surface-100 disabled:opacity-40"
+              >
+                Send test
+              </button>
+            )}
+          </div>
+
+          {testError && <p className="text-xs text-red-400">{testError}</p>}
+
+          {deliveries.length > 0 && (
+            <div>
+              <p className="text-surface-100 text-sm font-medium">Recent deliveries</p>
+              <ul className="mt-2 space-y-1 max-h-48 overflow-y-auto">
+                {deliveries.map((delivery) => (
+                  <li key={delivery.id} className="flex items-center gap-3 text-xs">
+                    <span
+                      className={
+                        delivery.delivered
+                          ? "text-primary-400"
+                          : delivery.error
+                            ? "text-red-400"
+                            : "text-surface-400"
+                      }
+                    >
+                      {delivery.delivered ? delivery.status : delivery.error ? "Failed" : "Sending"}
+                    </span>
+                    <span className="flex-1 text-surface-300 font-mono truncate" title={delivery.error ?? delivery.url}>
+                      {delivery.url}
+                    </span>
+                    <span className="text-surface-500">
+                      {delivery.event === "test" ? "Test" : delivery.device}
+                      {delivery.attempts > 1 && ` · ${delivery.attempts} tries`}
+                    </span>
+                    <span className="text-surface-500">{new Date(delivery.at * 1000).toLocaleTimeString()}</span>
+                  </li>
+                ))}
+              </ul>
+            </div>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
@@ -1,4 +1,4 @@
-import type { AudioDevice, DeviceGroup, AppSettings } from "../types";
+import type { AudioDevice, DeviceGroup, AppSettings, InventoryEntry } from "../types";
 
 // Check if demo mode is enabled via:
 // 1. URL parameter: ?demo
@@ -17,11 +17,11 @@ export const isDemoMode = () => {
 
 // Realistic mock devices
 export const mockDevices: AudioDevice[] = [
-  { id: "dev-1", name: "Speakers (Realtek High Definition Audio)", is_default: true },
-  { id: "dev-2", name: "Headphones (HyperX Cloud II Wireless)", is_default: false },
-  { id: "dev-3", name: "NVIDIA RTX Voice", is_default: false },
-  { id: "dev-4", name: "Focusrite Scarlett 2i2 USB", is_default: false },
-  { id: "dev-5", name: "LG TV (HDMI)", is_default: false },
+  { id: "dev-1", name: "Speakers (Realtek High Definition Audio)", is_default: true, kind: "speakers" },
+  { id: "dev-2", name: "Headphones (HyperX Cloud II Wireless)", is_default: false, kind: "headphones" },
+  { id: "dev-3", name: "NVIDIA RTX Voice", is_default: false, kind: "other" },
+  { id: "dev-4", name: "Focusrite Scarlett 2i2 USB", is_default: false, kind: "speakers" },
+  { id: "dev-5", name: "LG TV (HDMI)", is_default: false, alias: "Living Room TV", kind: "display" },
 ];
 
 // Realistic mock groups
@@ -59,9 +59,38 @@ export const mockGroups: DeviceGroup[] = [
   },
 ];
 
+// Mock inventory - only the offline device needs history for the demo
+const now = Math.floor(Date.now() / 1000);
+export const mockInventory: InventoryEntry[] = [
+  {
+    id: "dev-offline",
+    last_known_name: "Old USB Headset",
+    container_id: null,
+    first_seen: now - 90 * 24 * 60 * 60,
+    last_seen: now - 3 * 24 * 60 * 60,
+    connected: false,
+  },
+];
+
 // Mock settings
 export const mockSettings: AppSettings = {
   run_on_startup: true,
   start_minimized: false,
   close_to_tray: true,
+  http_api: { enabled: false, port: 7879, token: "", allowed_origins: [] },
+  mqtt: { enabled: false, host: "localhost", port: 1883, username: "", password: "", base_topic: "soundshift" },
+  osc: { enabled: false, bind_address: "127.0.0.1", port: 9000, allowed_hosts: [], feedback_address: "" },
+  deep_links: { enabled: true, confirm: true },
+  hooks: { pre_switch: "", post_switch: "", timeout_secs: 10 },
+  webhooks: { enabled: false, urls: [] },
+  tray: {
+    left_click: { action: "show_window" },
+    middle_click: { action: "cycle_group", group_id: "grp-1" },
+    scroll_volume: true,
+    volume_step: 2,
+  },
+  notifications: { enabled: true, template: "Switched to {device}", suppress_fullscreen: false, actions: true },
+  osd: { enabled: false, position: "bottom", duration_ms: 1500 },
+  chime: { enabled: false, sound: "", volume: 50, devices: {} },
+  speech: { enabled: false, replace_notification: true },
 };
@@ -1,14 +1,26 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import type { AudioDevice } from "../types";
+import type { AudioDevice, InventoryEntry } from "../types";
 import DeviceRow from "../components/devices/DeviceRow";
-import { isDemoMode, mockDevices } from "../mocks/demoData";
+import { isDemoMode, mockDevices, mockInventory } from "../mocks/demoData";
+import { formatLastSeen } from "../utils/time";
+
+// Offline devices older than this are offered for cleanup
+const STALE_AFTER_DAYS = 30;
 
 export default function DevicesPage() {
   const [devices, setDevices] = useState<AudioDevice[]>([]);
+  const [inventory, setInventory] = useState<InventoryEntry[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  const [renamingId, setRenamingId] = useState<string | null>(null);
+  const [aliasInput, setAliasInput] = useState("");
+  // Device whose speaker test is open, and its channels
+  const [testingId, setTestingId] = useState<string | null>(null);
+  const [channels, setChannels] = useState<string[]>([]);
+  const [playing, setPlaying] = useState(false);
+  const [testError, setTestError] = useState<string | null>(null);
 
   const fetchDevices = async () => {
     try {
@@ -17,6 +29,7 @@ export default function DevicesPage() {
       // Use mock data in demo mode
       if (isDemoMode()) {
         setDevices(mockDevices);
+        setInventory(mockInventory);
         setError(null);
         setLoading(false);
         return;
@@ -24,6 +37,7 @@ export default function DevicesPage() {
 
       const result = await invoke<AudioDevice[]>("get_audio_devices");
       setDevices(result);
+      setInventory(await invoke<InventoryEntry[]>("get_device_inventory"));
       setError(null);
     } catch (e) {
       setError(e as string);
@@ -41,6 +55,86 @@ export default function DevicesPage() {
     }
   };
 
+  const startRename = (device: AudioDevice) => {
+    setRenamingId(device.id);
+    setAliasInput(device.alias ?? "");
+  };
+
+  const saveAlias = async (deviceId: string) => {
+    setRenamingId(null);
+
+    if (isDemoMode()) {
+      const alias = aliasInput.trim() || null;
+      setDevices(devices.map(d => (d.id === deviceId ? { ...d, alias } : d)));
+      return;
+    }
+
+    try {
+      // An empty alias falls back to the OS name
+      await invoke("set_device_alias", { deviceId, alias: aliasInput });
+      await fetchDevices();
+    } catch (e) {
+      setError(e as string);
+    }
+  };
+
+  const forgetDevice = async (deviceId: string) => {
+    try {
+      await invoke("forget_device", { deviceId });
+      await fetchDevices();
+    } catch (e) {
+      setError(e as string);
+    }
+  };
+
+  const toggleTest = async (deviceId: string) => {
+    setTestError(null);
+    if (testingId === deviceId) {
+      setTestingId(null);
+      return;
+    }
+
+    setTestingId(deviceId);
+    setChannels([]);
+    if (isDemoMode()) {
+      setChannels(["Left", "Right"]);
+      return;
+    }
+    try {
+      setChannels(await invoke<string[]>("get_output_channels", { deviceId }));
+    } catch (e) {
+      setTestError(e as string);
+    }
+  };
+
+  // Plays on one channel, or on each in turn when channel is null
+  const playTestTone = async (deviceId: string, channel: number | null) => {
+    setTestError(null);
+    if (isDemoMode()) {
+      return;
+    }
+    setPlaying(true);
+    try {
+      await invoke("play_test_tone", { deviceId, channel });
+    } catch (e) {
+      setTestError(e as string);
+    } finally {
+      setPlaying(false);
+    }
+  };
+
+  const pruneInventory = async () => {
+    try {
+      await invoke<number>("prune_device_inventory", { olderThanDays: STALE_AFTER_DAYS });
+      await fetchDevices();
+    } catch (e) {
+      setError(e as string);
+    }
+  };
+
+  // Devices seen before that aren't connected right now
+  const offlineDevices = inventory.filter(e => !e.connected && !devices.some(d => d.id === e.id));
+
   useEffect(() => {
     fetchDevices();
 
@@ -90,14 +184,132 @@ export default function DevicesPage() {
         </div>
       ) : (
         <div className="grid gap-3">
-          {devices.map((device) => (
-            <DeviceRow
-              key={device.id}
-              device={device}
-              isActive={device.is_default}
-              onClick={() => setDefaultDevice(device.id)}
-            />
-          ))}
+          {devices.map((device) =>
+            renamingId === device.id ? (
+              <form
+                key={device.id}
+                onSubmit={(e) => {
+                  e.preventDefault();
+                  saveAlias(device.id);
+                }}
+                className="flex items-center gap-3 p-4 rounded-xl bg-surface-750 border border-surface-700"
+              >
+                <input
+                  type="text"
+                  value={aliasInput}
+                  onChange={(e) => setAliasInput(e.target.value)}
+                  onKeyDown={(e) => e.key === "Escape" && setRenamingId(null)}
+                  placeholder={device.name}
+                  autoFocus
+                  className="flex-1 px-3 py-2 bg-surface-800 border border-surface-700 rounded-lg text-sm text-surface-100 placeholder-surface-500 focus:outline-none focus:border-primary-500"
+                />
+                <button
+                  type="submit"
+                  className="px-3 py-2 bg-primary-500 hover:bg-primary-400 rounded-lg text-sm text-white transition-colors"
+                >
+                  Save
+                </button>
+                <button
+                  type="button"
+                  onClick={() => setRenamingId(null)}
+                  className="px-3 py-2 text-sm text-surface-400 hover:text-surface-100 transition-colors"
+                >
+                  Cancel
+                </button>
+              </form>
+            ) : (
+              <div key={device.id} className="space-y-2">
+                <div className="flex items-center gap-2">
+                  <div className="flex-1 min-w-0">
+                    <DeviceRow
+                      device={device}
+                      isActive={device.is_default}
+                      onClick={() => setDefaultDevice(device.id)}
+                    />
+                  </div>
+                  <button
+                    onClick={() => toggleTest(device.id)}
+                    className={`p-2 transition-colors ${
+                      testingId === device.id ? "text-primary-400" : "text-surface-500 hover:text-surface-100"
+                    }`}
+                    title="Test speakers"
+                  >
+                    <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 19V6l12-3v13M9 19c0 1.105-1.343 2-3 2s-3-.895-3-2 1.343-2 3-2 3 .895 3 2zm12-3c0 1.105-1.343 2-3 2s-3-.895-3-2 1.343-2 3-2 3 .895 3 2zM9 10l12-3" />
+                    </svg>
+                  </button>
+                  <button
+                    onClick={() => startRename(device)}
+                    className="p-2 text-surface-500 hover:text-surface-100 transition-colors"
+                    title="Rename device"
+                  >
+                    <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                      <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M15.232 5.232l3.536 3.536M9 13l6.536-6.536a2.5 2.5 0 113.536 3.536L12.536 16.536 9 17l.464-3.536z" />
+                    </svg>
+                  </button>
+                </div>
+
+                {testingId === device.id && (
+                  <div className="flex flex-wrap items-center gap-2 px-4 py-3 rounded-xl bg-surface-800/50">
+                    <span className="text-xs text-surface-400 mr-1">Play a tone on</span>
+                    <button
+                      onClick={() => playTestTone(device.id, null)}
+                      disabled={playing || channels.length === 0}
+                      className="px-3 py-1.5 bg-primary-500 hover:bg-primary-400 disabled:opacity-40 rounded-lg transition-colors text-xs font-medium text-white"
+                    >
+                      Each channel in turn
+                    </button>
+                    {channels.map((name, channel) => (
+                      <button
+                        key={channel}
+                        onClick={() => playTestTone(device.id, channel)}
+                        disabled={playing}
+                        className="px-3 py-1.5 bg-surface-750 hover:bg-surface-700 disabled:opacity-40 rounded-lg transition-colors text-xs text-surface-300 hover:text-surface-100"
+                      >
+                        {name}
+                      </button>
+                    ))}
+                    {testError && <span className="w-full text-xs text-red-400">{testError}</span>}
+                  </div>
+                )}
+              </div>
+            )
+          )}
+        </div>
+      )}
+
+      {!loading && offlineDevices.length > 0 && (
+        <div className="space-y-3">
+          <div className="flex items-center justify-between">
+            <h3 className="text-base font-medium text-surface-100">Previously seen</h3>
+            <button
+              onClick={pruneInventory}
+              className="text-xs text-surface-400 hover:text-surface-100 transition-colors"
+              title="Devices still used by a group are kept"
+            >
+              Forget devices not seen in {STALE_AFTER_DAYS} days
+            </button>
+          </div>
+          <div className="grid gap-2">
+            {offlineDevices.map((entry) => (
+              <div
+                key={entry.id}
+                className="flex items-center gap-3 px-4 py-3 rounded-xl bg-surface-800/50"
+              >
+                <span className="flex-1 truncate text-sm text-surface-400">{entry.last_known_name}</span>
+                <span className="text-xs text-surface-500">Last seen {formatLastSeen(entry.last_seen)}</span>
+                <button
+                  onClick={() => forgetDevice(entry.id)}
+                  className="p-1 text-surface-500 hover:text-danger-400 transition-colors"
+                  title="Forget device"
+                >
+                  <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                    <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M6 18L18 6M6 6l12 12" />
+                  </svg>
+                </button>
+              </div>
+            ))}
+          </div>
         </div>
       )}
     </div>
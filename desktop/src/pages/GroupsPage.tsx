@@ -1,14 +1,16 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import type { AudioDevice, DeviceGroup } from "../types";
+import type { AudioDevice, DeviceGroup, InventoryEntry } from "../types";
 import GroupCard from "../components/groups/GroupCard";
 import GroupModal from "../components/groups/GroupModal";
-import { isDemoMode, mockDevices, mockGroups } from "../mocks/demoData";
+import { isDemoMode, mockDevices, mockGroups, mockInventory } from "../mocks/demoData";
 
 export default function GroupsPage() {
   const [groups, setGroups] = useState<DeviceGroup[]>([]);
   const [devices, setDevices] = useState<AudioDevice[]>([]);
+  const [inventory, setInventory] = useState<InventoryEntry[]>([]);
+  const [aliases, setAliases] = useState<Record<string, string>>({});
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [modalOpen, setModalOpen] = useState(false);
@@ -22,17 +24,22 @@ export default function GroupsPage() {
       if (isDemoMode()) {
         setGroups(mockGroups);
         setDevices(mockDevices);
+        setInventory(mockInventory);
         setError(null);
         setLoading(false);
         return;
       }
 
-      const [groupsResult, devicesResult] = await Promise.all([
+      const [groupsResult, devicesResult, inventoryResult, aliasesResult] = await Promise.all([
         invoke<DeviceGroup[]>("get_groups"),
         invoke<AudioDevice[]>("get_audio_devices"),
+        invoke<InventoryEntry[]>("get_device_inventory"),
+        invoke<Record<string, string>>("get_device_aliases"),
       ]);
       setGroups(groupsResult);
       setDevices(devicesResult);
+      setInventory(inventoryResult);
+      setAliases(aliasesResult);
       setError(null);
     } catch (e) {
       setError(e as string);
@@ -44,9 +51,9 @@ export default function GroupsPage() {
   const handleSaveGroup = async (group: Omit<DeviceGroup, "id" | "current_index"> & { id?: string }) => {
     try {
       if (group.id) {
-        await invoke("update_group", { group: { ...group, current_index: editingGroup?.current_index ?? 0 } });
+        await invoke("update_group", { group: { ...editingGroup, ...group, current_index: editingGroup?.current_index ?? 0 } });
       } else {
-        await invoke("create_group", { name: group.name, devices: group.devices, shortcut: group.shortcut });
+        await invoke("create_group", { name: group.name, devices: group.devices, shortcut: group.shortcut, hooks: group.hooks, hideNotifications: group.hide_notifications });
       }
       await fetchData();
       setModalOpen(false);
@@ -106,9 +113,15 @@ export default function GroupsPage() {
       fetchData();
     });
 
+    // Listen for external edits to the TOML config file
+    const unlistenConfig = listen("config-reloaded", () => {
+      fetchData();
+    });
+
     return () => {
       unlistenSwitch.then((fn) => fn());
       unlistenDevices.then((fn) => fn());
+      unlistenConfig.then((fn) => fn());
     };
   }, []);
 
@@ -166,6 +179,8 @@ export default function GroupsPage() {
               key={group.id}
               group={group}
               onlineDevices={devices}
+              inventory={inventory}
+              aliases={aliases}
               onEdit={() => handleEditGroup(group)}
               onDelete={() => handleDeleteGroup(group.id)}
               onCycle={() => handleCycleGroup(group.id)}
@@ -183,6 +198,7 @@ export default function GroupsPage() {
         }}
         onSave={handleSaveGroup}
         devices={devices}
+        aliases={aliases}
         editingGroup={editingGroup}
       />
     </div>
@@ -0,0 +1,43 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { Osd } from "../types";
+
+// Contents of the overlay window shown after a switch
+export default function OsdPage() {
+  const [osd, setOsd] = useState<Osd | null>(null);
+
+  useEffect(() => {
+    // The window itself is transparent, only the card is drawn
+    document.documentElement.style.background = "transparent";
+    document.body.style.background = "transparent";
+
+    invoke<Osd | null>("get_osd").then(setOsd);
+
+    const unlisten = listen<Osd>("osd-show", (event) => setOsd(event.payload));
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  if (!osd) {
+    return null;
+  }
+
+  return (
+    <div className="h-screen p-2 select-none cursor-default">
+      <div className="h-full flex items-center gap-4 px-5 bg-surface-900/90 border border-surface-700 rounded-2xl">
+        <div className="flex-1 min-w-0">
+          <p className="text-base font-medium text-surface-100 truncate">{osd.device}</p>
+          {osd.group && <p className="text-xs text-surface-400 truncate">{osd.group}</p>}
+        </div>
+        {osd.volume !== null && (
+          <span className={`text-sm font-medium ${osd.muted ? "text-surface-500" : "text-primary-400"}`}>
+            {osd.muted ? "Muted" : `${osd.volume}%`}
+          </span>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -1,16 +1,47 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { enable, disable, isEnabled } from "@tauri-apps/plugin-autostart";
-import type { AppSettings } from "../types";
+import type { AppSettings, ConfigDir } from "../types";
 import { isDemoMode, mockSettings } from "../mocks/demoData";
+import HttpApiSection from "../components/settings/HttpApiSection";
+import MqttSection from "../components/settings/MqttSection";
+import OscSection from "../components/settings/OscSection";
+import DeepLinkSection from "../components/settings/DeepLinkSection";
+import HooksSection from "../components/settings/HooksSection";
+import WebhooksSection from "../components/settings/WebhooksSection";
+import TraySection from "../components/settings/TraySection";
+import NotificationsSection from "../components/settings/NotificationsSection";
+import OsdSection from "../components/settings/OsdSection";
+import ChimeSection from "../components/settings/ChimeSection";
+import SpeechSection from "../components/settings/SpeechSection";
 
 export default function SettingsPage() {
   const [settings, setSettings] = useState<AppSettings>({
     run_on_startup: false,
     start_minimized: false,
     close_to_tray: true,
+    http_api: { enabled: false, port: 7879, token: "", allowed_origins: [] },
+    mqtt: { enabled: false, host: "localhost", port: 1883, username: "", password: "", base_topic: "soundshift" },
+    osc: { enabled: false, bind_address: "127.0.0.1", port: 9000, allowed_hosts: [], feedback_address: "" },
+    deep_links: { enabled: true, confirm: true },
+    hooks: { pre_switch: "", post_switch: "", timeout_secs: 10 },
+    webhooks: { enabled: false, urls: [] },
+    tray: {
+      left_click: { action: "show_window" },
+      middle_click: { action: "nothing" },
+      scroll_volume: true,
+      volume_step: 2,
+    },
+    notifications: { enabled: true, template: "Switched to {device}", suppress_fullscreen: false, actions: true },
+    osd: { enabled: false, position: "bottom", duration_ms: 1500 },
+    chime: { enabled: false, sound: "", volume: 50, devices: {} },
+    speech: { enabled: false, replace_notification: true },
   });
   const [loading, setLoading] = useState(true);
+  const [configFilePath, setConfigFilePath] = useState<string | null>(null);
+  const [configDir, setConfigDir] = useState<ConfigDir | null>(null);
+  const [configError, setConfigError] = useState<string | null>(null);
 
   const fetchSettings = async () => {
     try {
@@ -25,6 +56,8 @@ export default function SettingsPage() {
 
       const result = await invoke<AppSettings>("get_settings");
       setSettings(result);
+      setConfigFilePath(await invoke<string | null>("get_config_file_path"));
+      setConfigDir(await invoke<ConfigDir>("get_config_dir"));
 
       // Sync autostart state with system
       const autostartEnabled = await isEnabled();
@@ -50,6 +83,12 @@ export default function SettingsPage() {
 
       await invoke("update_settings", { settings: newSettings });
 
+      // The backend generates a token when the API is enabled without one
+      if (key === "http_api") {
+        const saved = await invoke<AppSettings>("get_settings");
+        setSettings(prev => ({ ...prev, http_api: saved.http_api }));
+      }
+
       // Handle autostart toggle
       if (key === "run_on_startup") {
         if (value) {
@@ -65,8 +104,38 @@ export default function SettingsPage() {
     }
   };
 
+  const exportConfigFile = async () => {
+    try {
+      const path = await invoke<string>("export_config_file");
+      setConfigFilePath(path);
+      setConfigError(null);
+    } catch (e) {
+      setConfigError(e as string);
+    }
+  };
+
   useEffect(() => {
     fetchSettings();
+
+    // Skip event listeners in demo mode (Tauri APIs not available in browser)
+    if (isDemoMode()) {
+      return;
+    }
+
+    // Listen for external edits to the TOML config file
+    const unlistenReload = listen("config-reloaded", () => {
+      setConfigError(null);
+      fetchSettings();
+    });
+
+    const unlistenError = listen<string>("config-error", (event) => {
+      setConfigError(event.payload);
+    });
+
+    return () => {
+      unlistenReload.then((fn) => fn());
+      unlistenError.then((fn) => fn());
+    };
   }, []);
 
   if (loading) {
@@ -147,6 +216,103 @@ export default function SettingsPage() {
           </div>
         </div>
 
+        <HttpApiSection
+          settings={settings.http_api}
+          onChange={(httpApi) => updateSetting("http_api", httpApi)}
+        />
+
+        <MqttSection
+          settings={settings.mqtt}
+          onChange={(mqtt) => updateSetting("mqtt", mqtt)}
+        />
+
+        <OscSection
+          settings={settings.osc}
+          onChange={(osc) => updateSetting("osc", osc)}
+        />
+
+        <DeepLinkSection
+          settings={settings.deep_links}
+          onChange={(deepLinks) => updateSetting("deep_links", deepLinks)}
+        />
+
+        <HooksSection
+          settings={settings.hooks}
+          onChange={(hooks) => updateSetting("hooks", hooks)}
+        />
+
+        <WebhooksSection
+          settings={settings.webhooks}
+          onChange={(webhooks) => updateSetting("webhooks", webhooks)}
+        />
+
+        <TraySection
+          settings={settings.tray}
+          onChange={(tray) => updateSetting("tray", tray)}
+        />
+
+        <NotificationsSection
+          settings={settings.notifications}
+          onChange={(notifications) => updateSetting("notifications", notifications)}
+        />
+
+        <OsdSection
+          settings={settings.osd}
+          onChange={(osd) => updateSetting("osd", osd)}
+        />
+
+        <ChimeSection
+          settings={settings.chime}
+          onChange={(chime) => updateSetting("chime", chime)}
+        />
+
+        <SpeechSection
+          settings={settings.speech}
+          onChange={(speech) => updateSetting("speech", speech)}
+        />
+
+        {/* Config File */}
+        <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6 space-y-4">
+          <div>
+            <h3 className="text-base font-medium text-surface-100">Config file</h3>
+            <p className="text-xs text-surface-500 mt-0.5">
+              Keep groups and settings in a plain-text TOML file. Edits are picked up automatically.
+            </p>
+          </div>
+
+          {configDir && (
+            <div>
+              <p className="text-surface-100 text-sm font-medium">
+                Data folder
+                {configDir.source === "portable" && (
+                  <span className="ml-2 text-xs text-primary-400">Portable</span>
+                )}
+                {(configDir.source === "flag" || configDir.source === "env") && (
+                  <span className="ml-2 text-xs text-primary-400">Custom</span>
+                )}
+              </p>
+              <p className="text-xs text-surface-500 font-mono break-all mt-0.5">{configDir.path}</p>
+            </div>
+          )}
+
+          {configFilePath ? (
+            <p className="text-xs text-surface-300 font-mono break-all">{configFilePath}</p>
+          ) : (
+            <button
+              onClick={exportConfigFile}
+              className="px-4 py-2 bg-surface-750 hover:bg-surface-700 rounded-xl transition-all duration-200 text-sm text-surface-300 hover:text-surface-100"
+            >
+              Create config file
+            </button>
+          )}
+
+          {configError && (
+            <div className="bg-danger-500/10 border border-danger-500/20 rounded-xl p-3 text-xs text-danger-400">
+              {configError}
+            </div>
+          )}
+        </div>
+
         {/* About */}
         <div className="bg-surface-850 rounded-2xl border border-surface-750 p-6">
           <h3 className="text-base font-medium text-surface-100 mb-2">About</h3>
@@ -3,12 +3,36 @@ export interface AudioDevice {
   id: string;
   name: string;
   is_default: boolean;
+  container_id?: string | null;
+  alias?: string | null;
+  kind?: DeviceKind;
 }
 
+// Form factor reported by the device's driver
+export type DeviceKind = "speakers" | "headphones" | "display" | "other";
+
 // Device reference stored in a group (persists even when device is offline)
 export interface GroupDevice {
   id: string;
   name: string;
+  container_id?: string | null;
+  match_pattern?: DeviceMatcher | null;
+  matcher?: DeviceMatcher | null;
+}
+
+// Dynamic group entry matching live devices by name
+export interface DeviceMatcher {
+  kind: "wildcard" | "regex";
+  pattern: string;
+}
+
+// Record of a group entry following its device to a new ID
+export interface DeviceRelink {
+  old_id: string;
+  new_id: string;
+  name: string;
+  matched_by: "id" | "container" | "name" | "pattern";
+  at: number;
 }
 
 // Device group with shortcut
@@ -18,6 +42,26 @@ export interface DeviceGroup {
   devices: GroupDevice[];
   shortcut: string | null;
   current_index: number;
+  current_device_id?: string | null;
+  relinks?: DeviceRelink[];
+  hooks?: GroupHooks;
+  hide_notifications?: boolean;
+}
+
+// Shell commands run around a switch; empty runs nothing
+export interface GroupHooks {
+  pre_switch: string;
+  post_switch: string;
+}
+
+// Device remembered in the inventory, online or not
+export interface InventoryEntry {
+  id: string;
+  last_known_name: string;
+  container_id: string | null;
+  first_seen: number;
+  last_seen: number;
+  connected: boolean;
 }
 
 // Application settings
@@ -25,6 +69,157 @@ export interface AppSettings {
   run_on_startup: boolean;
   start_minimized: boolean;
   close_to_tray: boolean;
+  http_api: HttpApiSettings;
+  mqtt: MqttSettings;
+  osc: OscSettings;
+  deep_links: DeepLinkSettings;
+  hooks: HookSettings;
+  webhooks: WebhookSettings;
+  tray: TraySettings;
+  notifications: NotificationSettings;
+  osd: OsdSettings;
+  chime: ChimeSettings;
+  speech: SpeechSettings;
+}
+
+// Spoken announcement of the new device after a switch
+export interface SpeechSettings {
+  enabled: boolean;
+  replace_notification: boolean;
+}
+
+// Sound played on the new device once it's the default
+export interface ChimeSettings {
+  enabled: boolean;
+  // Empty plays the built-in chime
+  sound: string;
+  volume: number;
+  devices: Record<string, DeviceChime>;
+}
+
+// A device's own chime; missing fields use the global ones
+export interface DeviceChime {
+  sound?: string;
+  volume?: number;
+}
+
+// On-screen overlay shown briefly after each switch
+export interface OsdSettings {
+  enabled: boolean;
+  position: OsdPosition;
+  duration_ms: number;
+}
+
+export type OsdPosition = "top_left" | "top" | "top_right" | "center" | "bottom_left" | "bottom" | "bottom_right";
+
+// What the overlay shows
+export interface Osd {
+  device: string;
+  group: string | null;
+  volume: number | null;
+  muted: boolean;
+}
+
+// The notification shown after a group switch
+export interface NotificationSettings {
+  enabled: boolean;
+  template: string;
+  suppress_fullscreen: boolean;
+  actions: boolean;
+}
+
+// What clicks and the mouse wheel do on the tray icon
+export interface TraySettings {
+  left_click: TrayAction;
+  middle_click: TrayAction;
+  scroll_volume: boolean;
+  volume_step: number;
+}
+
+export type TrayAction =
+  | { action: "show_window" }
+  | { action: "cycle_group"; group_id: string }
+  | { action: "toggle_mute" }
+  | { action: "nothing" };
+
+// Connection to an MQTT broker
+export interface MqttSettings {
+  enabled: boolean;
+  host: string;
+  port: number;
+  username: string;
+  password: string;
+  base_topic: string;
+}
+
+// Shell commands run around every switch
+export interface HookSettings {
+  pre_switch: string;
+  post_switch: string;
+  timeout_secs: number;
+}
+
+// One hook command run for one switch
+export interface HookRun {
+  id: number;
+  stage: "pre_switch" | "post_switch";
+  command: string;
+  device: string;
+  at: number;
+  duration_ms: number;
+  exit_code: number | null;
+  timed_out: boolean;
+  error: string | null;
+  success: boolean;
+  output: string[];
+}
+
+// URLs every switch is posted to
+export interface WebhookSettings {
+  enabled: boolean;
+  urls: string[];
+}
+
+// One payload sent to one webhook URL, updated as it is retried
+export interface WebhookDelivery {
+  id: number;
+  url: string;
+  event: string;
+  device: string;
+  at: number;
+  attempts: number;
+  status: number | null;
+  error: string | null;
+  delivered: boolean;
+}
+
+// Handling of soundshift:// links
+export interface DeepLinkSettings {
+  enabled: boolean;
+  confirm: boolean;
+}
+
+// UDP listener for OSC controllers
+export interface OscSettings {
+  enabled: boolean;
+  bind_address: string;
+  port: number;
+  allowed_hosts: string[];
+  feedback_address: string;
+}
+
+// Opt-in REST API on localhost
+export interface HttpApiSettings {
+  enabled: boolean;
+  port: number;
+  token: string;
+  allowed_origins: string[];
+}
+
+// Where the store and config file live, and what chose that location
+export interface ConfigDir {
+  path: string;
+  source: "flag" | "env" | "portable" | "default";
 }
 
 // Store schema
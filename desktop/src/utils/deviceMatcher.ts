@@ -0,0 +1,22 @@
+import type { AudioDevice, DeviceMatcher, GroupDevice } from "../types";
+
+// Mirrors DeviceMatcher::matches in the backend: both kinds are case-insensitive
+function matcherRegex(matcher: DeviceMatcher): RegExp | null {
+  try {
+    if (matcher.kind === "regex") {
+      return new RegExp(matcher.pattern, "i");
+    }
+    const escaped = matcher.pattern.replace(/[.+^${}()|[\]\\]/g, "\\$&");
+    return new RegExp(`^${escaped.replace(/\*/g, ".*").replace(/\?/g, ".")}$`, "i");
+  } catch {
+    return null;
+  }
+}
+
+// Whether a live device is covered by a group entry (fixed ID or pattern)
+export function matchesDevice(entry: GroupDevice, device: AudioDevice): boolean {
+  if (!entry.matcher) {
+    return entry.id === device.id;
+  }
+  return matcherRegex(entry.matcher)?.test(device.name) ?? false;
+}
@@ -0,0 +1,21 @@
+// Formats a Unix timestamp (seconds) as e.g. "just now", "5 minutes ago", "3 days ago"
+export function formatLastSeen(timestamp: number): string {
+  const seconds = Math.max(0, Math.floor(Date.now() / 1000) - timestamp);
+
+  const units: [string, number][] = [
+    ["year", 365 * 24 * 60 * 60],
+    ["month", 30 * 24 * 60 * 60],
+    ["day", 24 * 60 * 60],
+    ["hour", 60 * 60],
+    ["minute", 60],
+  ];
+
+  for (const [unit, size] of units) {
+    const count = Math.floor(seconds / size);
+    if (count >= 1) {
+      return `${count} ${unit}${count !== 1 ? "s" : ""} ago`;
+    }
+  }
+
+  return "just now";
+}